@@ -1,17 +1,63 @@
-use syn::{Attribute, Lit, Meta, NestedMeta};
+use syn::{
+    self, parse::Parser, punctuated::Punctuated, Attribute, Expr, ExprPath, Ident, Lit, Meta, MetaList, MetaNameValue, NestedMeta,
+    Path, Type, Visibility,
+};
 
-#[derive(Default)]
+use manifest;
+use naming;
+
+#[derive(Default, Clone)]
 pub(crate) struct AttrInfo {
     pub(crate) names: Vec<String>,
+    // Words turned off explicitly, with `word = false`
+    pub(crate) off_names: Vec<String>,
     pub(crate) name_vals: Vec<(String, String)>,
+    // name(name=value, ...)
+    pub(crate) lists: Vec<(String, Vec<(String, String)>)>,
 }
 
 pub(crate) struct MacroConfig {
     pub(crate) inblock: bool,
     pub(crate) non_public: bool,
+    pub(crate) skip_conflicts: bool,
+    pub(crate) hidden: bool,
+    pub(crate) inherit_doc: bool,
+    pub(crate) doc_alias: bool,
+    pub(crate) see_also: bool,
+    pub(crate) type_generics: bool,
+    pub(crate) doc_example: Option<String>,
+    pub(crate) doc_example_init: Option<String>,
+    pub(crate) forward_attrs: Option<String>,
+    pub(crate) drop_attrs: Option<String>,
+    pub(crate) add_attrs: Option<String>,
+    pub(crate) cfg: Option<String>,
+    pub(crate) doc_cfg: bool,
+    pub(crate) if_variant: bool,
+    pub(crate) opt_variant: bool,
+    // Name template of each variants
+    pub(crate) each: Option<String>,
+    pub(crate) setters: bool,
+    pub(crate) vis: Option<String>,
     pub(crate) prefix: String,
+    pub(crate) suffix: String,
+    pub(crate) name_template: Option<String>,
+    pub(crate) strip_prefix: Option<String>,
+    pub(crate) case: Option<String>,
+    pub(crate) deprecated_prefix: Option<String>,
     pub(crate) impl_doc: String,
     pub(crate) doc: String,
+    pub(crate) profile: Option<String>,
+    pub(crate) trait_: Option<String>,
+    pub(crate) builder: Option<String>,
+    pub(crate) finish: Option<String>,
+    pub(crate) validate: Option<String>,
+    pub(crate) validate_error: Option<String>,
+    pub(crate) builder_default: bool,
+    // Group names, and the doc strings of their impl blocks
+    pub(crate) groups: Vec<(String, String)>,
+    pub(crate) include: Option<String>,
+    pub(crate) exclude: Option<String>,
+    pub(crate) strict: bool,
 }
 
 #[derive(Default)]
@@ -19,10 +65,83 @@ pub(crate) struct MethodConfig {
     pub(crate) inblock: bool,
     pub(crate) non_public: bool,
     pub(crate) skip: bool,
+    pub(crate) skip_conflicts: bool,
+    pub(crate) hidden: bool,
+    pub(crate) inherit_doc: bool,
+    pub(crate) doc_alias: bool,
+    pub(crate) see_also: bool,
+    pub(crate) doc_example: Option<String>,
+    pub(crate) doc_example_init: Option<String>,
+    pub(crate) forward_attrs: Option<String>,
+    pub(crate) drop_attrs: Option<String>,
+    pub(crate) add_attrs: Option<String>,
+    pub(crate) cfg: Option<String>,
+    pub(crate) doc_cfg: bool,
+    pub(crate) if_variant: bool,
+    pub(crate) opt_variant: bool,
+    pub(crate) each: bool,
+    pub(crate) each_name: Option<String>,
+    pub(crate) extend: bool,
+    pub(crate) from_iter: bool,
+    pub(crate) op: Option<String>,
+    pub(crate) nested: bool,
+    pub(crate) nested_chain: bool,
+    pub(crate) setter: bool,
+    pub(crate) setter_into: bool,
+    pub(crate) required: bool,
+    pub(crate) group: Option<String>,
+    pub(crate) vis: Option<String>,
     pub(crate) prefix: Option<String>,
+    pub(crate) suffix: Option<String>,
+    pub(crate) name_template: Option<String>,
+    pub(crate) strip_prefix: Option<String>,
+    pub(crate) case: Option<String>,
     pub(crate) rename: Option<String>,
     pub(crate) name: Option<String>,
+    pub(crate) deprecated_alias: Option<String>,
     pub(crate) doc: Option<String>,
+    pub(crate) flag: bool,
+    pub(crate) flag_off: Option<String>,
+    // Names, and the arguments passed to the chaining method
+    pub(crate) presets: Vec<(String, String)>,
+    // Parameter options, by parameter position (`self` is 0)
+    pub(crate) params: Vec<(usize, ParamConfig)>,
+}
+
+// `#[fluent(...)]` options of a struct deriving `Fluent`.
+pub(crate) struct DeriveConfig {
+    pub(crate) prefix: String,
+    pub(crate) set: bool,
+    pub(crate) vis: String,
+}
+
+// `#[fluent(...)]` options of a field of a struct deriving `Fluent`.
+#[derive(Default)]
+pub(crate) struct FieldConfig {
+    pub(crate) skip: bool,
+    pub(crate) set: bool,
+    pub(crate) into: bool,
+    pub(crate) strip_option: bool,
+    pub(crate) rename: Option<String>,
+    pub(crate) each: Option<String>,
+    pub(crate) vis: Option<String>,
+}
+
+#[derive(Default, Clone)]
+pub(crate) struct ParamConfig {
+    pub(crate) into: bool,
+    pub(crate) strip_option: bool,
+    pub(crate) default: Option<String>,
+}
+
+impl Default for DeriveConfig {
+    fn default() -> Self {
+        Self {
+            prefix: "with_".into(),
+            set: false,
+            vis: "pub".into(),
+        }
+    }
 }
 
 impl Default for MacroConfig {
@@ -30,9 +149,43 @@ impl Default for MacroConfig {
         Self {
             inblock: false,
             non_public: false,
+            skip_conflicts: false,
+            hidden: false,
+            inherit_doc: false,
+            doc_alias: false,
+            see_also: false,
+            type_generics: false,
+            doc_example: None,
+            doc_example_init: None,
+            forward_attrs: None,
+            drop_attrs: None,
+            add_attrs: None,
+            cfg: None,
+            doc_cfg: false,
+            if_variant: false,
+            opt_variant: false,
+            each: None,
+            setters: false,
+            vis: None,
             prefix: "with_".into(),
+            suffix: "".into(),
+            name_template: None,
+            strip_prefix: None,
+            case: None,
+            deprecated_prefix: None,
             doc: "The chaining (fluent) equivalent of [`%f%()`].".into(),
             impl_doc: "Chaining (fluent) methods for [`%t%`].".into(),
+            profile: None,
+            trait_: None,
+            builder: None,
+            finish: None,
+            validate: None,
+            validate_error: None,
+            builder_default: false,
+            groups: Vec::new(),
+            include: None,
+            exclude: None,
+            strict: false,
         }
     }
 }
@@ -49,12 +202,14 @@ pub(crate) fn parse_config_from_attr(attr: &Attribute) -> Result<AttrInfo, Strin
                             Meta::Word(ident) => attr_info.names.push(ident.to_string()),
                             Meta::NameValue(name_value) => match name_value.lit {
                                 Lit::Str(ref ls) => attr_info.name_vals.push((name_value.ident.to_string(), ls.value())),
+                                Lit::Bool(ref lb) if lb.value => attr_info.names.push(name_value.ident.to_string()),
+                                Lit::Bool(_) => attr_info.off_names.push(name_value.ident.to_string()),
                                 _ => Err(format!(
                                     "expected a string literal value in a name_vlue pair, found: {:?}",
                                     name_value.lit
                                 ))?,
                             },
-                            Meta::List(l) => Err(format!("expected a meta word or name=value, found meta list: {:?}", l))?,
+                            Meta::List(l) => attr_info.lists.push((l.ident.to_string(), parse_name_vals(l)?)),
                         },
                         NestedMeta::Literal(l) => Err(format!("expected a meta word or name=value, found literal: {:?}", l))?,
                     }
@@ -69,6 +224,19 @@ pub(crate) fn parse_config_from_attr(attr: &Attribute) -> Result<AttrInfo, Strin
     Ok(attr_info)
 }
 
+fn parse_name_vals(meta_list: &MetaList) -> Result<Vec<(String, String)>, String> {
+    let mut name_vals = Vec::with_capacity(meta_list.nested.len());
+    for nm in &meta_list.nested {
+        match nm {
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue { ident, lit: Lit::Str(ls), .. })) => {
+                name_vals.push((ident.to_string(), ls.value()))
+            },
+            _ => Err(format!("expected name=value pairs in {}(...), found: {:?}", meta_list.ident, nm))?,
+        }
+    }
+    Ok(name_vals)
+}
+
 macro_rules! err_if_set {
     ($ty:ident, $var:ident, $field:ident, $val:expr) => {
         if $var.$field != $ty::default().$field {
@@ -79,64 +247,344 @@ macro_rules! err_if_set {
     };
 }
 
-pub(crate) fn get_proc_macro_config(attr_info: AttrInfo) -> Result<MacroConfig, String> {
+pub(crate) fn get_proc_macro_config(mut attr_info: AttrInfo) -> Result<MacroConfig, String> {
+    let mut manifest_config = manifest::get_manifest_config()?;
     let mut config = MacroConfig::default();
+    let in_manifest = |e: String| format!("{} (in Cargo.toml package metadata)", e);
 
-    for name in attr_info.names {
+    let preset_info = match take_name_val(&mut attr_info, "preset") {
+        Some(preset) => Some(manifest_config.take_preset(&preset)?),
+        None => None,
+    };
+
+    // Precedence: default < manifest < preset < attribute
+    set_proc_macro_config(&mut config, manifest_config.defaults).map_err(in_manifest)?;
+    if let Some(preset_info) = preset_info {
+        set_proc_macro_config(&mut config, preset_info).map_err(in_manifest)?;
+    }
+    set_proc_macro_config(&mut config, attr_info)?;
+
+    if let Some(vis) = &config.vis {
+        check_vis(vis)?;
+    }
+
+    if let Some(doc_example) = &config.doc_example {
+        check_doc_example(doc_example)?;
+    }
+
+    for attr_paths in config.forward_attrs.iter().chain(&config.drop_attrs) {
+        parse_attr_paths(attr_paths)?;
+    }
+
+    if let Some(add_attrs) = &config.add_attrs {
+        parse_attrs(add_attrs)?;
+    }
+
+    if let Some(cfg) = &config.cfg {
+        parse_cfg(cfg)?;
+    }
+
+    for patterns in config.include.iter().chain(&config.exclude) {
+        naming::parse_patterns(patterns)?;
+    }
+
+    if let Some(name_template) = &config.name_template {
+        naming::check_template("name_template", name_template)?;
+    }
+
+    if let Some(each) = &config.each {
+        naming::check_template("each", each)?;
+    }
+
+    if let Some(case) = &config.case {
+        naming::check_case(case)?;
+    }
+
+    if let Some(profile) = &config.profile {
+        if profile.is_empty() {
+            Err("invalid empty profile attribute")?;
+        }
+    }
+
+    if let Some(trait_) = &config.trait_ {
+        if syn::parse_str::<Ident>(trait_).is_err() {
+            Err(format!("invalid trait attribute, expected an identifier: {:?}", trait_))?;
+        }
+        if config.inblock {
+            Err("inblock can't be set if trait is set")?;
+        }
+    }
+
+    check_builder(&config)?;
+
+    Ok(config)
+}
+
+fn check_builder(config: &MacroConfig) -> Result<(), String> {
+    let builder = match &config.builder {
+        Some(builder) => builder,
+        None if config.finish.is_some()
+            || config.validate.is_some()
+            || config.validate_error.is_some()
+            || config.builder_default =>
+        {
+            Err("finish, validate, validate_error and builder_default can't be set if builder is not set")?
+        },
+        None => return Ok(()),
+    };
+
+    if syn::parse_str::<Ident>(builder).is_err() {
+        Err(format!("invalid builder attribute, expected an identifier: {:?}", builder))?;
+    }
+    if config.trait_.is_some() || config.inblock {
+        Err("trait and inblock can't be set if builder is set")?;
+    }
+    if let Some(finish) = &config.finish {
+        naming::make_ident(finish).map_err(|_| format!("invalid finish attribute, expected a method name: {:?}", finish))?;
+    }
+
+    match (&config.validate, &config.validate_error) {
+        (Some(validate), Some(validate_error)) => {
+            if syn::parse_str::<ExprPath>(validate).is_err() {
+                Err(format!("invalid validate attribute, expected a path (e.g. \"Self::validate\"): {:?}", validate))?;
+            }
+            if syn::parse_str::<Type>(validate_error).is_err() {
+                Err(format!("invalid validate_error attribute, expected a type: {:?}", validate_error))?;
+            }
+        },
+        (None, None) => (),
+        _ => Err("validate and validate_error must be set together")?,
+    }
+
+    Ok(())
+}
+
+fn set_proc_macro_config(config: &mut MacroConfig, attr_info: AttrInfo) -> Result<(), String> {
+    check_duplicates(&attr_info)?;
+
+    // Turning a word off resets it, so later layers can override the manifest defaults
+    let words = attr_info.names.into_iter().map(|name| (name, true));
+    for (name, on) in words.chain(attr_info.off_names.into_iter().map(|name| (name, false))) {
         match &*name {
-            "inblock" => err_if_set!(MacroConfig, config, inblock, true),
-            "non_public" => err_if_set!(MacroConfig, config, non_public, true),
+            "inblock" => config.inblock = on,
+            "non_public" => config.non_public = on,
+            "skip_conflicts" => config.skip_conflicts = on,
+            "hidden" => config.hidden = on,
+            "inherit_doc" => config.inherit_doc = on,
+            "doc_alias" => config.doc_alias = on,
+            "see_also" => config.see_also = on,
+            "type_generics" => config.type_generics = on,
+            "doc_example" => config.doc_example = if on { Some("ignore".into()) } else { None },
+            "doc_cfg" => config.doc_cfg = on,
+            "if_variant" => config.if_variant = on,
+            "opt_variant" => config.opt_variant = on,
+            "each" => config.each = if on { Some("{}s".into()) } else { None },
+            "setters" => config.setters = on,
+            "strict" => config.strict = on,
+            "builder_default" => config.builder_default = on,
             _ => Err(format!("invalid attribute word: {}", name))?,
         }
     }
 
     for (name, val) in attr_info.name_vals {
         match (&*name, val) {
-            ("prefix", val) => err_if_set!(MacroConfig, config, prefix, val),
-            ("impl_doc", val) => err_if_set!(MacroConfig, config, impl_doc, val),
-            ("doc", val) => err_if_set!(MacroConfig, config, doc, val),
+            ("doc_example", val) => config.doc_example = Some(val),
+            ("doc_example_init", val) => config.doc_example_init = Some(val),
+            ("forward_attrs", val) => config.forward_attrs = Some(val),
+            ("drop_attrs", val) => config.drop_attrs = Some(val),
+            ("add_attrs", val) => config.add_attrs = Some(val),
+            ("cfg", val) => config.cfg = Some(val),
+            ("each", val) => config.each = Some(val),
+            ("include", val) => config.include = Some(val),
+            ("exclude", val) => config.exclude = Some(val),
+            ("vis", val) => config.vis = Some(val),
+            ("prefix", val) => config.prefix = val,
+            ("suffix", val) => config.suffix = val,
+            ("name_template", val) => config.name_template = Some(val),
+            ("strip_prefix", val) => config.strip_prefix = Some(val),
+            ("case", val) => config.case = Some(val),
+            ("deprecated_prefix", val) => config.deprecated_prefix = Some(val),
+            ("impl_doc", val) => config.impl_doc = val,
+            ("doc", val) => config.doc = val,
+            ("profile", val) => config.profile = Some(val),
+            ("trait", val) => config.trait_ = Some(val),
+            ("builder", val) => config.builder = Some(val),
+            ("finish", val) => config.finish = Some(val),
+            ("validate", val) => config.validate = Some(val),
+            ("validate_error", val) => config.validate_error = Some(val),
             _ => Err(format!("invalid name in a name_value pair: {}", name))?,
         }
     }
 
-    if config.prefix.is_empty() {
-        Err("invalid empty prefix attribute")?;
+    for (name, name_vals) in attr_info.lists {
+        match &*name {
+            // Groups set in a later layer replace earlier ones with the same name
+            "groups" => for (group, doc) in name_vals {
+                config.groups.retain(|(g, _)| *g != group);
+                config.groups.push((group, doc));
+            },
+            _ => Err(format!("invalid attribute list: {}", name))?,
+        }
     }
 
-    Ok(config)
+    Ok(())
+}
+
+// Options may only be set once per attribute (or manifest table).
+fn check_duplicates(attr_info: &AttrInfo) -> Result<(), String> {
+    let mut seen = Vec::with_capacity(attr_info.names.len() + attr_info.name_vals.len() + attr_info.lists.len());
+    let names = attr_info.names.iter().chain(&attr_info.off_names);
+    let names = names.chain(attr_info.name_vals.iter().map(|(name, _)| name));
+    let names = names.chain(attr_info.lists.iter().map(|(name, _)| name));
+    for name in names {
+        if seen.contains(&name) {
+            Err(format!("{} is already set", name))?;
+        }
+        seen.push(name);
+    }
+    Ok(())
+}
+
+// Only block-level words can be turned on by an earlier layer, so only they can be turned off.
+fn check_no_off_names(attr_info: &AttrInfo) -> Result<(), String> {
+    match attr_info.off_names.first() {
+        Some(name) => Err(format!("{} = false only applies to block-level options", name)),
+        None => Ok(()),
+    }
+}
+
+// Remove a name=value option, and return its value.
+pub(crate) fn take_name_val(attr_info: &mut AttrInfo, name: &str) -> Option<String> {
+    let pos = attr_info.name_vals.iter().position(|(n, _)| n == name)?;
+    Some(attr_info.name_vals.remove(pos).1)
 }
 
 pub(crate) fn get_method_config(attr_info: AttrInfo, pre_config: Option<MethodConfig>) -> Result<MethodConfig, String> {
-    let mut config = match pre_config {
-        Some(pre_config) => pre_config,
-        None => MethodConfig::default(),
-    };
+    check_no_off_names(&attr_info)?;
+    let mut config = pre_config.unwrap_or_default();
 
     for name in attr_info.names {
         match &*name {
             "inblock" => err_if_set!(MethodConfig, config, inblock, true),
             "non_public" => err_if_set!(MethodConfig, config, non_public, true),
             "skip" => err_if_set!(MethodConfig, config, skip, true),
+            "skip_conflicts" => err_if_set!(MethodConfig, config, skip_conflicts, true),
+            "hidden" => err_if_set!(MethodConfig, config, hidden, true),
+            "inherit_doc" => err_if_set!(MethodConfig, config, inherit_doc, true),
+            "doc_alias" => err_if_set!(MethodConfig, config, doc_alias, true),
+            "see_also" => err_if_set!(MethodConfig, config, see_also, true),
+            "doc_example" => err_if_set!(MethodConfig, config, doc_example, Some("ignore".into())),
+            "doc_cfg" => err_if_set!(MethodConfig, config, doc_cfg, true),
+            "if_variant" => err_if_set!(MethodConfig, config, if_variant, true),
+            "opt_variant" => err_if_set!(MethodConfig, config, opt_variant, true),
+            "flag" => err_if_set!(MethodConfig, config, flag, true),
+            "each" => err_if_set!(MethodConfig, config, each, true),
+            "extend" => err_if_set!(MethodConfig, config, extend, true),
+            "from_iter" => err_if_set!(MethodConfig, config, from_iter, true),
+            "nested" => err_if_set!(MethodConfig, config, nested, true),
+            // Implies nested
+            "nested_chain" => {
+                err_if_set!(MethodConfig, config, nested_chain, true);
+                err_if_set!(MethodConfig, config, nested, true);
+            },
+            "setter" => err_if_set!(MethodConfig, config, setter, true),
+            "required" => err_if_set!(MethodConfig, config, required, true),
+            // Implies setter
+            "setter_into" => {
+                err_if_set!(MethodConfig, config, setter_into, true);
+                err_if_set!(MethodConfig, config, setter, true);
+            },
             _ => Err(format!("invalid attribute word: {}", name))?,
         }
     }
 
     for (name, val) in attr_info.name_vals {
         match (&*name, val) {
+            ("doc_example", val) => err_if_set!(MethodConfig, config, doc_example, Some(val)),
+            ("doc_example_init", val) => err_if_set!(MethodConfig, config, doc_example_init, Some(val)),
+            ("forward_attrs", val) => err_if_set!(MethodConfig, config, forward_attrs, Some(val)),
+            ("drop_attrs", val) => err_if_set!(MethodConfig, config, drop_attrs, Some(val)),
+            ("add_attrs", val) => err_if_set!(MethodConfig, config, add_attrs, Some(val)),
+            ("cfg", val) => err_if_set!(MethodConfig, config, cfg, Some(val)),
+            ("each", val) => {
+                err_if_set!(MethodConfig, config, each, true);
+                config.each_name = Some(val);
+            },
+            ("op", val) => err_if_set!(MethodConfig, config, op, Some(val)),
+            ("group", val) => err_if_set!(MethodConfig, config, group, Some(val)),
+            ("vis", val) => err_if_set!(MethodConfig, config, vis, Some(val)),
             ("prefix", val) => err_if_set!(MethodConfig, config, prefix, Some(val)),
+            ("suffix", val) => err_if_set!(MethodConfig, config, suffix, Some(val)),
+            ("name_template", val) => err_if_set!(MethodConfig, config, name_template, Some(val)),
+            ("strip_prefix", val) => err_if_set!(MethodConfig, config, strip_prefix, Some(val)),
+            ("case", val) => err_if_set!(MethodConfig, config, case, Some(val)),
             ("name", val) => err_if_set!(MethodConfig, config, name, Some(val)),
             ("rename", val) => err_if_set!(MethodConfig, config, rename, Some(val)),
+            ("deprecated_alias", val) => err_if_set!(MethodConfig, config, deprecated_alias, Some(val)),
             ("doc", val) => err_if_set!(MethodConfig, config, doc, Some(val)),
             _ => Err(format!("invalid name in a name_value pair: {}", name))?,
         }
     }
+
+    for (name, name_vals) in attr_info.lists {
+        match &*name {
+            "flag" => {
+                err_if_set!(MethodConfig, config, flag, true);
+                for (name, val) in name_vals {
+                    match &*name {
+                        // The on method is the chaining method
+                        "on" => err_if_set!(MethodConfig, config, name, Some(val)),
+                        "off" => err_if_set!(MethodConfig, config, flag_off, Some(val)),
+                        _ => Err(format!("invalid name in flag(...): {}", name))?,
+                    }
+                }
+            },
+            "preset" => {
+                let (mut preset_name, mut args) = (None, None);
+                for (name, val) in name_vals {
+                    match &*name {
+                        "name" if preset_name.is_none() => preset_name = Some(val),
+                        "args" if args.is_none() => args = Some(val),
+                        _ => Err(format!("invalid or repeated name in preset(...): {}", name))?,
+                    }
+                }
+                match (preset_name, args) {
+                    (Some(preset_name), Some(args)) => config.presets.push((preset_name, args)),
+                    _ => Err("preset(...) expects both name and args")?,
+                }
+            },
+            _ => Err(format!("invalid attribute list: {}", name))?,
+        }
+    }
+
     if config.name.is_some() {
-        match (&config.rename, &config.prefix) {
+        match (&config.rename, &config.prefix, &config.suffix, &config.name_template) {
+            (None, None, None, None) => (),
+            _ => Err("rename, prefix, suffix and/or name_template attributes can't be set if name is set")?,
+        }
+        match (&config.strip_prefix, &config.case) {
             (None, None) => (),
-            _ => Err("rename and/or prefix attributes can't be set if name is set")?,
+            _ => Err("strip_prefix and/or case attributes can't be set if name is set")?,
         }
     }
 
+    if config.nested && config.setter {
+        Err("nested and setter can't both be set")?;
+    }
+
+    // Those variants return the builder with and without the required method called
+    if config.required && (config.if_variant || config.opt_variant || config.each || config.nested_chain) {
+        Err("if_variant, opt_variant, each and nested_chain can't be set if required is set")?;
+    }
+
+    if config.inherit_doc && config.doc.is_some() {
+        Err("inherit_doc can't be set if doc is set")?;
+    }
+
+    if config.name_template.is_some() && (config.prefix.is_some() || config.suffix.is_some()) {
+        Err("prefix and/or suffix attributes can't be set if name_template is set")?;
+    }
+
     if let Some(name) = &config.name {
         if name.is_empty() {
             Err("invalid empty name attribute")?;
@@ -149,11 +597,220 @@ pub(crate) fn get_method_config(attr_info: AttrInfo, pre_config: Option<MethodCo
         }
     }
 
-    if let Some(prefix) = &config.prefix {
-        if prefix.is_empty() {
-            Err("invalid empty prefix attribute")?;
+    if let Some(vis) = &config.vis {
+        check_vis(vis)?;
+    }
+
+    if let Some(doc_example) = &config.doc_example {
+        check_doc_example(doc_example)?;
+    }
+
+    for attr_paths in config.forward_attrs.iter().chain(&config.drop_attrs) {
+        parse_attr_paths(attr_paths)?;
+    }
+
+    if let Some(add_attrs) = &config.add_attrs {
+        parse_attrs(add_attrs)?;
+    }
+
+    if let Some(cfg) = &config.cfg {
+        parse_cfg(cfg)?;
+    }
+
+    if let Some(name_template) = &config.name_template {
+        naming::check_template("name_template", name_template)?;
+    }
+
+    if let Some(case) = &config.case {
+        naming::check_case(case)?;
+    }
+
+    if let Some(op) = &config.op {
+        check_op(op)?;
+    }
+
+    for name in config.flag_off.iter().chain(&config.each_name) {
+        naming::make_ident(name)?;
+    }
+
+    for (name, args) in &config.presets {
+        naming::make_ident(name)?;
+        parse_args(args)?;
+    }
+
+    Ok(config)
+}
+
+pub(crate) fn get_derive_config(attr_info: AttrInfo, pre_config: Option<DeriveConfig>) -> Result<DeriveConfig, String> {
+    check_no_off_names(&attr_info)?;
+    let mut config = pre_config.unwrap_or_default();
+
+    for name in attr_info.names {
+        match &*name {
+            "set" => err_if_set!(DeriveConfig, config, set, true),
+            _ => Err(format!("invalid struct attribute word: {}", name))?,
+        }
+    }
+
+    for (name, val) in attr_info.name_vals {
+        match (&*name, val) {
+            ("prefix", val) => err_if_set!(DeriveConfig, config, prefix, val),
+            ("vis", val) => err_if_set!(DeriveConfig, config, vis, val),
+            _ => Err(format!("invalid name in a struct name_value pair: {}", name))?,
+        }
+    }
+
+    if let Some((name, _)) = attr_info.lists.first() {
+        Err(format!("invalid struct attribute list: {}", name))?;
+    }
+
+    check_vis(&config.vis)?;
+
+    Ok(config)
+}
+
+pub(crate) fn get_field_config(attr_info: AttrInfo, pre_config: Option<FieldConfig>) -> Result<FieldConfig, String> {
+    check_no_off_names(&attr_info)?;
+    let mut config = pre_config.unwrap_or_default();
+
+    for name in attr_info.names {
+        match &*name {
+            "skip" => err_if_set!(FieldConfig, config, skip, true),
+            "set" => err_if_set!(FieldConfig, config, set, true),
+            "into" => err_if_set!(FieldConfig, config, into, true),
+            "strip_option" => err_if_set!(FieldConfig, config, strip_option, true),
+            _ => Err(format!("invalid field attribute word: {}", name))?,
+        }
+    }
+
+    for (name, val) in attr_info.name_vals {
+        match (&*name, val) {
+            ("rename", val) => err_if_set!(FieldConfig, config, rename, Some(val)),
+            ("each", val) => err_if_set!(FieldConfig, config, each, Some(val)),
+            ("vis", val) => err_if_set!(FieldConfig, config, vis, Some(val)),
+            _ => Err(format!("invalid name in a field name_value pair: {}", name))?,
         }
     }
 
+    if let Some((name, _)) = attr_info.lists.first() {
+        Err(format!("invalid field attribute list: {}", name))?;
+    }
+
+    let other_opts = config.set || config.into || config.strip_option || config.rename.is_some() || config.each.is_some();
+    if config.skip && (other_opts || config.vis.is_some()) {
+        Err("skip conflicts with all other field options")?;
+    }
+
+    if config.each.is_some() && (config.into || config.strip_option) {
+        Err("each conflicts with into and strip_option")?;
+    }
+
+    for name in config.rename.iter().chain(&config.each) {
+        if name.is_empty() {
+            Err("invalid empty rename or each attribute")?;
+        }
+    }
+
+    if let Some(vis) = &config.vis {
+        check_vis(vis)?;
+    }
+
     Ok(config)
 }
+
+// Options from `#[fluent(...)]` parameter attributes.
+pub(crate) fn get_param_config(attr_info: AttrInfo, pre_config: Option<ParamConfig>) -> Result<ParamConfig, String> {
+    check_no_off_names(&attr_info)?;
+    let mut config = pre_config.unwrap_or_default();
+
+    for name in attr_info.names {
+        match &*name {
+            "into" => err_if_set!(ParamConfig, config, into, true),
+            "strip_option" => err_if_set!(ParamConfig, config, strip_option, true),
+            _ => Err(format!("invalid parameter attribute word: {}", name))?,
+        }
+    }
+
+    for (name, val) in attr_info.name_vals {
+        match (&*name, val) {
+            ("default", val) => err_if_set!(ParamConfig, config, default, Some(val)),
+            _ => Err(format!("invalid name in a parameter name_value pair: {}", name))?,
+        }
+    }
+
+    if let Some((name, _)) = attr_info.lists.first() {
+        Err(format!("invalid parameter attribute list: {}", name))?;
+    }
+
+    if let Some(default) = &config.default {
+        if config.into || config.strip_option {
+            Err("into and/or strip_option attributes can't be set if default is set")?;
+        }
+        syn::parse_str::<Expr>(default).map_err(|_| format!("invalid default attribute, expected an expression: {:?}", default))?;
+    }
+
+    Ok(config)
+}
+
+fn check_vis(vis: &str) -> Result<(), String> {
+    match syn::parse_str::<Visibility>(vis) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("invalid vis attribute, expected a visibility (e.g. \"pub(crate)\"): {:?}", vis))?,
+    }
+}
+
+// Operator traits, and their methods. The by-value counterparts are named without `Assign`.
+pub(crate) const ASSIGN_OPS: &[(&str, &str)] = &[
+    ("AddAssign", "add_assign"),
+    ("SubAssign", "sub_assign"),
+    ("MulAssign", "mul_assign"),
+    ("DivAssign", "div_assign"),
+    ("RemAssign", "rem_assign"),
+    ("BitAndAssign", "bitand_assign"),
+    ("BitOrAssign", "bitor_assign"),
+    ("BitXorAssign", "bitxor_assign"),
+    ("ShlAssign", "shl_assign"),
+    ("ShrAssign", "shr_assign"),
+];
+
+fn check_op(op: &str) -> Result<(), String> {
+    if !ASSIGN_OPS.iter().any(|(op_trait, _)| *op_trait == op) {
+        let op_traits: Vec<_> = ASSIGN_OPS.iter().map(|(op_trait, _)| *op_trait).collect();
+        Err(format!("invalid op attribute, expected one of {}: {:?}", op_traits.join(", "), op))?;
+    }
+    Ok(())
+}
+
+fn check_doc_example(doc_example: &str) -> Result<(), String> {
+    match doc_example {
+        "ignore" | "no_run" => Ok(()),
+        _ => Err(format!("invalid doc_example attribute, expected \"ignore\" or \"no_run\": {:?}", doc_example))?,
+    }
+}
+
+// A comma-separated list of attribute paths, e.g. `"allow, cfg_attr"`.
+pub(crate) fn parse_attr_paths(attr_paths: &str) -> Result<Vec<Path>, String> {
+    let paths: Punctuated<Path, Token![,]> = Punctuated::parse_terminated
+        .parse_str(attr_paths)
+        .map_err(|_| format!("invalid attribute path list: {:?}", attr_paths))?;
+    Ok(paths.into_iter().collect())
+}
+
+// Outer attributes, e.g. `"#[inline] #[allow(unused)]"`.
+pub(crate) fn parse_attrs(attrs: &str) -> Result<Vec<Attribute>, String> {
+    Attribute::parse_outer
+        .parse_str(attrs)
+        .map_err(|_| format!("invalid attributes, expected e.g. \"#[inline]\": {:?}", attrs))
+}
+
+// Comma-separated call arguments, e.g. `"Color::Red, 1"`.
+pub(crate) fn parse_args(args: &str) -> Result<Punctuated<Expr, Token![,]>, String> {
+    Punctuated::parse_terminated
+        .parse_str(args)
+        .map_err(|_| format!("invalid preset args, expected comma-separated expressions: {:?}", args))
+}
+
+// A cfg predicate, e.g. `"feature = \"fluent\""`.
+pub(crate) fn parse_cfg(cfg: &str) -> Result<Meta, String> {
+    syn::parse_str(cfg).map_err(|_| format!("invalid cfg attribute, expected a cfg predicate: {:?}", cfg))
+}
@@ -0,0 +1,152 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use syn::{self, Attribute, Data, DeriveInput, Field, Ident, Index, Member, Visibility};
+
+use config::{self, AttrInfo, DeriveConfig, FieldConfig};
+use naming;
+use type_utils as t;
+
+pub(crate) fn gen_fluent_from_derive(input: &DeriveInput) -> Result<TokenStream2, String> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => Err("derive(Fluent) only applies to structs")?,
+    };
+
+    let derive_config = fluent_attrs(&input.attrs)?
+        .into_iter()
+        .try_fold(None, |pre, attr_info| config::get_derive_config(attr_info, pre).map(Some))?
+        .unwrap_or_default();
+
+    let mut methods = Vec::new();
+    for (idx, field) in fields.iter().enumerate() {
+        let field_methods = fluent_attrs(&field.attrs)?
+            .into_iter()
+            .try_fold(None, |pre, attr_info| config::get_field_config(attr_info, pre).map(Some))
+            .and_then(|field_config| fluent_field_methods(field, idx, &derive_config, &field_config.unwrap_or_default()));
+        let field_name = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => idx.to_string(),
+        };
+        methods.extend(field_methods.map_err(|e| format!("field {}: {}", field_name, e))?);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#methods)*
+        }
+    })
+}
+
+fn fluent_attrs(attrs: &[Attribute]) -> Result<Vec<AttrInfo>, String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path == parse_quote! { fluent })
+        .map(config::parse_config_from_attr)
+        .collect()
+}
+
+fn fluent_field_methods(
+    field: &Field,
+    idx: usize,
+    derive_config: &DeriveConfig,
+    field_config: &FieldConfig,
+) -> Result<Vec<TokenStream2>, String> {
+    if field_config.skip {
+        return Ok(Vec::new());
+    }
+
+    let member = match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(idx)),
+    };
+    let name = match (&field_config.rename, &field.ident) {
+        (Some(rename), _) => rename.clone(),
+        (None, Some(ident)) => naming::ident_name(ident),
+        (None, None) => idx.to_string(),
+    };
+    // Tuple fields have no name to give to the parameter
+    let arg = match &field.ident {
+        Some(ident) => ident.clone(),
+        None => Ident::new("value", Span::call_site()),
+    };
+
+    let vis: Visibility = syn::parse_str(field_config.vis.as_ref().unwrap_or(&derive_config.vis)).map_err(|e| e.to_string())?;
+
+    let field_ty = &field.ty;
+    let ty = if field_config.strip_option {
+        t::option_inner_ty(field_ty).ok_or("strip_option requires an Option<T> field")?
+    } else {
+        field_ty
+    };
+    let arg_ty = if field_config.into {
+        quote! { impl ::std::convert::Into<#ty> }
+    } else {
+        quote! { #ty }
+    };
+    let value = match (field_config.into, field_config.strip_option) {
+        (true, true) => quote! { ::std::option::Option::Some(#arg.into()) },
+        (true, false) => quote! { #arg.into() },
+        (false, true) => quote! { ::std::option::Option::Some(#arg) },
+        (false, false) => quote! { #arg },
+    };
+
+    // Field docs are copied, so the chaining method reads like the field
+    let field_docs: Vec<_> = field.attrs.iter().filter(|attr| attr.path == parse_quote! { doc }).collect();
+    let docs = if field_docs.is_empty() {
+        let doc = format!("Set `{}`.", name);
+        quote! { #[doc = #doc] }
+    } else {
+        quote! { #(#field_docs)* }
+    };
+
+    let mut methods = Vec::new();
+
+    let with_ident = naming::make_ident(&format!("{}{}", derive_config.prefix, name))?;
+    methods.push(quote! {
+        #docs
+        #[must_use]
+        #vis fn #with_ident(mut self, #arg: #arg_ty) -> Self {
+            self.#member = #value;
+            self
+        }
+    });
+
+    if derive_config.set || field_config.set {
+        let set_ident = naming::make_ident(&format!("set_{}", name))?;
+        methods.push(quote! {
+            #docs
+            #vis fn #set_ident(&mut self, #arg: #arg_ty) -> &mut Self {
+                self.#member = #value;
+                self
+            }
+        });
+    }
+
+    if let Some(each) = &field_config.each {
+        let each_ident = naming::make_ident(each)?;
+        let each_doc = format!("Add an item to `{}`.", name);
+        methods.push(quote! {
+            #[doc = #each_doc]
+            #[must_use]
+            #vis fn #each_ident(mut self, item: <#field_ty as ::std::iter::IntoIterator>::Item) -> Self {
+                ::std::iter::Extend::extend(&mut self.#member, ::std::iter::once(item));
+                self
+            }
+        });
+    }
+
+    Ok(methods)
+}
@@ -9,79 +9,696 @@
     file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
 */
 
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::ToTokens;
-use syn::{ImplItem, ImplItemMethod, ItemImpl};
+use syn::{
+    self, Attribute, Expr, ExprPath, FnArg, GenericParam, Ident, ImplItem, ImplItemMethod, ItemImpl, Stmt, TraitItem, Type,
+    Visibility,
+};
 
-use config::MacroConfig;
+use config::{self, MacroConfig, MethodConfig};
 use method as m;
+use naming;
+use params::ParamConfigs;
 use type_utils as t;
 
-pub(crate) fn gen_fluent_from_impl_block(impl_block: &ItemImpl, macro_config: &MacroConfig) -> Result<TokenStream2, String> {
+pub(crate) fn gen_fluent_from_impl_block(
+    impl_block: &ItemImpl,
+    macro_config: MacroConfig,
+    param_configs: &ParamConfigs,
+) -> Result<TokenStream2, String> {
     if impl_block.trait_.is_some() {
         Err("fluent_impl does not apply to trait impl blocks")?
     }
 
+    let mut impl_block = impl_block.clone();
+    let macro_configs = get_profiles(&mut impl_block, macro_config)?;
+    check_method_profiles(&impl_block, &macro_configs)?;
+    check_patterns(&impl_block, &macro_configs)?;
+
+    let generated = gen_fluent_methods(&impl_block, &macro_configs, param_configs)?;
+    let mut generated = check_conflicts(&impl_block, &macro_configs, generated)?;
+
+    let markers: Vec<Vec<Ident>> = (0..macro_configs.len()).map(|idx| required_markers(&generated, idx)).collect();
+    apply_typestate(&impl_block, &macro_configs, &markers, &mut generated);
+
+    for gen in &generated {
+        if let ImplItem::Method(method) = &mut impl_block.items[gen.pos] {
+            method.attrs.extend(gen.see_also.iter().cloned());
+        }
+    }
+
     let mut input = TokenStream2::new();
-    gen_fluent_inblock(&impl_block, macro_config)?.to_tokens(&mut input);
+    gen_fluent_inblock(&impl_block, &generated).to_tokens(&mut input);
 
-    let new_impl_block = gen_fluent_new_block(&impl_block, macro_config)?;
-    if !new_impl_block.items.is_empty() {
-        let mut block = new_impl_block;
-        let bare_ty_str = t::bare_ty_str(&block.self_ty)?;
-        let doc = macro_config.impl_doc.clone().replace("%t%", &bare_ty_str);
-        block.attrs.push(parse_quote! { #[doc = #doc] });
-        block.to_tokens(&mut input);
+    for (profile_idx, macro_config) in macro_configs.iter().enumerate() {
+        // Ungrouped methods first, then a block per group
+        let impl_docs = Some((None, &macro_config.impl_doc))
+            .into_iter()
+            .chain(macro_config.groups.iter().map(|(group, doc)| (Some(group), doc)));
+        for (group, impl_doc) in impl_docs {
+            let new_impl_block = gen_fluent_new_block(&impl_block, &generated, profile_idx, group);
+            if !new_impl_block.items.is_empty() {
+                gen_fluent_new_block_tokens(new_impl_block, macro_config, impl_doc, &markers[profile_idx])?.to_tokens(&mut input);
+            }
+        }
     }
 
+    for (macro_config, markers) in macro_configs.iter().zip(&markers) {
+        if let Some(builder) = &macro_config.builder {
+            gen_builder(&impl_block, builder, macro_config, markers)?.to_tokens(&mut input);
+        }
+    }
+
+    for gen in &generated {
+        gen.vis_check.to_tokens(&mut input);
+    }
+
+    gen_std_impls(&impl_block, &macro_configs)?.to_tokens(&mut input);
+
     Ok(input)
 }
 
-fn gen_fluent_inblock(impl_block: &ItemImpl, macro_config: &MacroConfig) -> Result<ItemImpl, String> {
-    let mut inblock_impl_block = impl_block.clone();
-    let mut added_count = 0;
-    let mut added_methods_pos: Vec<(usize, ImplItemMethod)> = Vec::with_capacity(16);
+// `Extend`, `FromIterator` and operator impls. These are generated once, from options not
+// targeting a profile, and gated by the block-level cfg shared by all profiles.
+fn gen_std_impls(impl_block: &ItemImpl, macro_configs: &[MacroConfig]) -> Result<TokenStream2, String> {
+    let mut impls = TokenStream2::new();
+    for impl_item in &impl_block.items {
+        if let ImplItem::Method(method) = impl_item {
+            let method_config = m::get_method_config(&method.attrs, None, None)?;
+            for profile in macro_configs.iter().filter_map(|c| c.profile.as_ref().map(String::as_str)) {
+                let profile_config = m::get_method_config(&method.attrs, Some(profile), None)?;
+                if profile_config.extend != method_config.extend
+                    || profile_config.from_iter != method_config.from_iter
+                    || profile_config.op != method_config.op
+                {
+                    Err(format!("extend, from_iter and op of {} can't target a profile", method.sig.ident))?;
+                }
+            }
+            let std_impls = method_config.extend || method_config.from_iter || method_config.op.is_some();
+            if std_impls && macro_configs.iter().any(|c| c.cfg != macro_configs[0].cfg) {
+                Err(format!("extend, from_iter and op of {} require all profiles to have the same cfg", method.sig.ident))?;
+            }
+            let (self_ty, generics) = (&impl_block.self_ty, &impl_block.generics);
+            m::collect_impls(method, self_ty, generics, &macro_configs[0], &method_config)?.to_tokens(&mut impls);
+            m::op_impls(method, self_ty, generics, &macro_configs[0], &method_config)?.to_tokens(&mut impls);
+        }
+    }
+    Ok(impls)
+}
+
+// A generated block of chaining methods, documented with `impl_doc`, or the extension trait.
+fn gen_fluent_new_block_tokens(
+    mut block: ItemImpl,
+    macro_config: &MacroConfig,
+    impl_doc: &str,
+    markers: &[Ident],
+) -> Result<TokenStream2, String> {
+    let ty_str = t::ty_str(&block.self_ty, macro_config.type_generics);
+    let mut doc = impl_doc.replace("%t%", &ty_str);
+    // `Self` in trait and builder docs is not the type
+    let link_base = match (&macro_config.trait_, &macro_config.builder) {
+        (None, None) => Some("Self".into()),
+        _ => t::ty_link_path(&block.self_ty),
+    };
+    if let Some(link_base) = link_base {
+        doc += &format!("\n\n [`{}`]: {}", ty_str, link_base);
+    }
+    let cfg_attrs = match &macro_config.cfg {
+        Some(cfg) => m::cfg_attrs(cfg, macro_config.doc_cfg)?,
+        None => Vec::new(),
+    };
+    if let Some(trait_) = &macro_config.trait_ {
+        gen_fluent_trait(block, trait_, macro_config.vis.as_ref(), &doc, &cfg_attrs)
+    } else {
+        if let Some(builder) = &macro_config.builder {
+            // `Self` is the builder in the new block, argument types still refer to the type
+            for item in &mut block.items {
+                if let ImplItem::Method(method) = item {
+                    builder_self_to_ty(method, &block.self_ty)?;
+                }
+            }
+            let builder_ident = Ident::new(builder, Span::call_site());
+            block.self_ty = if markers.is_empty() {
+                let (_, ty_generics, _) = block.generics.split_for_impl();
+                parse_quote! { #builder_ident #ty_generics }
+            } else {
+                // Chaining methods are available in all states
+                let args = t::generic_args(&block.generics);
+                parse_quote! { #builder_ident <#(#args,)* #(#markers),*> }
+            };
+            block.generics.params.extend(markers.iter().map(|marker| -> GenericParam { parse_quote! { #marker } }));
+        }
+        block.attrs.push(parse_quote! { #[doc = #doc] });
+        block.attrs.extend(cfg_attrs);
+        Ok(block.into_token_stream())
+    }
+}
+
+fn builder_self_to_ty(method: &mut ImplItemMethod, ty: &Type) -> Result<(), String> {
+    let decl = &mut method.sig.decl;
+    for arg in &mut decl.inputs {
+        if let FnArg::Captured(cap) = arg {
+            cap.ty = t::replace_self(&cap.ty, ty)?;
+        }
+    }
+    for param in &mut decl.generics.params {
+        *param = t::replace_self(param, ty)?;
+    }
+    if let Some(where_clause) = &mut decl.generics.where_clause {
+        *where_clause = t::replace_self(where_clause, ty)?;
+    }
+    Ok(())
+}
+
+// A chaining method generated from the method at `pos` in the impl block.
+struct Generated {
+    pos: usize,
+    origin: Ident,
+    profile_idx: usize,
+    inblock: bool,
+    skip_conflicts: bool,
+    method: ImplItemMethod,
+    vis_check: Option<TokenStream2>,
+    // Doc lines appended to the chain-able method
+    see_also: Vec<Attribute>,
+    group: Option<String>,
+    required: bool,
+}
 
-    for (pos, impl_item) in inblock_impl_block.items.iter().enumerate() {
+// Every other `#[fluent_impl]` attribute on the block describes an additional profile.
+// Those attributes are consumed here, so they don't get expanded again.
+fn get_profiles(impl_block: &mut ItemImpl, macro_config: MacroConfig) -> Result<Vec<MacroConfig>, String> {
+    let mut macro_configs = vec![macro_config];
+    let (profile_attrs, attrs) = impl_block
+        .attrs
+        .drain(..)
+        .partition(|a| a.path == parse_quote! { fluent_impl });
+    impl_block.attrs = attrs;
+
+    for attr in profile_attrs {
+        let attr_info = config::parse_config_from_attr(&attr)?;
+        macro_configs.push(config::get_proc_macro_config(attr_info)?);
+    }
+
+    if macro_configs.len() > 1 {
+        let mut profiles = Vec::with_capacity(macro_configs.len());
+        for macro_config in &macro_configs {
+            match &macro_config.profile {
+                Some(profile) if profiles.contains(&profile) => Err(format!("profile {} is set more than once", profile))?,
+                Some(profile) => profiles.push(profile),
+                None => Err("profile must be set if fluent_impl is applied more than once")?,
+            }
+        }
+    }
+
+    Ok(macro_configs)
+}
+
+fn check_method_profiles(impl_block: &ItemImpl, macro_configs: &[MacroConfig]) -> Result<(), String> {
+    for impl_item in &impl_block.items {
         if let ImplItem::Method(method) = impl_item {
-            let method_config = m::get_method_config(&method.attrs)?;
-            if m::try_fluentable(method, macro_config, &method_config).is_ok()
-                && (macro_config.inblock || method_config.inblock)
-            {
-                let ty = &inblock_impl_block.self_ty;
-                added_methods_pos.push((
-                    pos + added_count + 1,
-                    m::fluent_from_fluentable(method.clone(), &macro_config, ty)?,
-                ));
-                added_count += 1;
+            for profile in m::get_method_profiles(&method.attrs)? {
+                if !macro_configs.iter().any(|c| c.profile.as_ref() == Some(&profile)) {
+                    Err(format!("fluent_impl_opts of {} targets unknown profile {}", method.sig.ident, profile))?;
+                }
             }
         }
     }
+    Ok(())
+}
+
+// In strict mode, every include and exclude pattern must match a method in the block.
+fn check_patterns(impl_block: &ItemImpl, macro_configs: &[MacroConfig]) -> Result<(), String> {
+    let method_names: Vec<String> = impl_block
+        .items
+        .iter()
+        .filter_map(|impl_item| match impl_item {
+            ImplItem::Method(method) => Some(naming::ident_name(&method.sig.ident)),
+            _ => None,
+        })
+        .collect();
 
-    for (pos, method) in added_methods_pos {
-        inblock_impl_block.items.insert(pos, ImplItem::Method(method));
+    for macro_config in macro_configs.iter().filter(|c| c.strict) {
+        let patterns = [("include", &macro_config.include), ("exclude", &macro_config.exclude)];
+        for (option, patterns) in patterns.iter().filter_map(|(o, p)| p.as_ref().map(|p| (o, p))) {
+            for pattern in naming::parse_patterns(patterns)? {
+                if !method_names.iter().any(|name| naming::pattern_matches(&pattern, name)) {
+                    Err(format!("{} pattern {} matches no method in the impl block", option, pattern))?;
+                }
+            }
+        }
     }
-    Ok(inblock_impl_block)
+    Ok(())
 }
 
-fn gen_fluent_new_block(impl_block: &ItemImpl, macro_config: &MacroConfig) -> Result<ItemImpl, String> {
-    let mut new_impl_block = impl_block.clone();
-    new_impl_block.items = Vec::with_capacity(16);
-
-    if !macro_config.inblock {
-        for impl_item in &impl_block.items {
-            if let ImplItem::Method(method) = impl_item {
-                let method_config = m::get_method_config(&method.attrs)?;
-                if m::try_fluentable(method, macro_config, &method_config).is_ok() && !method_config.inblock {
-                    new_impl_block.items.push(ImplItem::Method(m::fluent_from_fluentable(
-                        method.clone(),
-                        macro_config,
-                        &new_impl_block.self_ty,
-                    )?));
+// Whether chaining methods are generated for a method, according to include and exclude patterns.
+fn in_scope(method: &ImplItemMethod, macro_config: &MacroConfig) -> Result<bool, String> {
+    let name = naming::ident_name(&method.sig.ident);
+    let any_matches = |patterns: &String| -> Result<bool, String> {
+        Ok(naming::parse_patterns(patterns)?.iter().any(|pattern| naming::pattern_matches(pattern, &name)))
+    };
+
+    let included = match &macro_config.include {
+        Some(include) => any_matches(include)?,
+        None => true,
+    };
+    let excluded = match &macro_config.exclude {
+        Some(exclude) => any_matches(exclude)?,
+        None => false,
+    };
+    Ok(included && !excluded)
+}
+
+fn gen_fluent_methods(
+    impl_block: &ItemImpl,
+    macro_configs: &[MacroConfig],
+    param_configs: &ParamConfigs,
+) -> Result<Vec<Generated>, String> {
+    let mut generated = Vec::with_capacity(impl_block.items.len() * macro_configs.len());
+    let ty = &impl_block.self_ty;
+
+    for (pos, impl_item) in impl_block.items.iter().enumerate() {
+        if let ImplItem::Method(method) = impl_item {
+            for (profile_idx, macro_config) in macro_configs.iter().enumerate() {
+                if !in_scope(method, macro_config)? {
+                    continue;
+                }
+                let pre_config = MethodConfig {
+                    params: param_configs.get(&method.sig.ident.to_string()).cloned().unwrap_or_default(),
+                    ..MethodConfig::default()
+                };
+                let profile = macro_config.profile.as_ref().map(String::as_str);
+                let method_config = m::get_method_config(&method.attrs, profile, Some(pre_config))?;
+                // Not skipped like other methods that are not chain-able
+                if method_config.nested || method_config.setter {
+                    m::check_accessor(method, &method_config)?;
+                }
+                if method_config.required && macro_config.builder.is_none() {
+                    Err(format!("required of {} only applies to builder profiles", method.sig.ident))?;
+                }
+                if m::try_fluentable(method, macro_config, &method_config).is_ok() {
+                    // Groups only apply to generated impl blocks, but are checked everywhere
+                    let group = match &method_config.group {
+                        Some(group) if !macro_config.groups.iter().any(|(g, _)| g == group) => {
+                            Err(format!("fluent_impl_opts of {} targets unknown group {}", method.sig.ident, group))?
+                        },
+                        Some(_) if macro_config.trait_.is_some() || macro_config.inblock => None,
+                        Some(_) if method_config.inblock && macro_config.builder.is_none() => None,
+                        Some(group) => Some(group.clone()),
+                        None => None,
+                    };
+                    let fluent_method = m::fluent_from_fluentable(method.clone(), macro_config, &method_config, ty)?;
+                    let aliases = m::fluent_aliases(method, &fluent_method, macro_config, &method_config)?;
+                    // Block-level variants don't apply to required methods
+                    let variants = if method_config.required {
+                        Vec::new()
+                    } else {
+                        m::fluent_variants(&fluent_method, macro_config, &method_config)?
+                    };
+                    let presets = m::fluent_presets(method, &fluent_method, macro_config, &method_config)?;
+                    let nested_chain = m::fluent_nested_chain(method, &fluent_method, macro_config, &method_config)?;
+                    let mut see_also = if macro_config.see_also || method_config.see_also {
+                        // The chaining method is not a method of the type in trait and builder profiles
+                        let link_base = match (&macro_config.trait_, &macro_config.builder) {
+                            (Some(name), _) | (_, Some(name)) => name.clone(),
+                            (None, None) => "Self".into(),
+                        };
+                        m::fluent_see_also(&fluent_method, &link_base)
+                    } else {
+                        Vec::new()
+                    };
+                    let mut vis_check = match method_config.vis.as_ref().or(macro_config.vis.as_ref()) {
+                        Some(_) => m::fluent_vis_check(&fluent_method, ty, &impl_block.generics),
+                        None => None,
+                    };
+                    let fluent_methods = Some(fluent_method).into_iter().chain(nested_chain);
+                    for fluent_method in fluent_methods.chain(aliases).chain(variants).chain(presets) {
+                        generated.push(Generated {
+                            pos,
+                            origin: method.sig.ident.clone(),
+                            profile_idx,
+                            // Method-level inblock doesn't apply to trait and builder profiles
+                            inblock: macro_config.trait_.is_none()
+                                && macro_config.builder.is_none()
+                                && (macro_config.inblock || method_config.inblock),
+                            skip_conflicts: macro_config.skip_conflicts || method_config.skip_conflicts,
+                            method: fluent_method,
+                            // Aliases share the signature, checking once is enough
+                            vis_check: vis_check.take(),
+                            see_also: see_also.split_off(0),
+                            group: group.clone(),
+                            required: method_config.required,
+                        });
+                    }
                 }
             }
         }
     }
 
-    Ok(new_impl_block)
+    Ok(generated)
+}
+
+// Check generated chaining method names against each other, and against items that exist
+// in the impl block already. Conflicting methods are dropped if skip_conflicts is set.
+fn check_conflicts(
+    impl_block: &ItemImpl,
+    macro_configs: &[MacroConfig],
+    generated: Vec<Generated>,
+) -> Result<Vec<Generated>, String> {
+    let existing: Vec<&Ident> = impl_block
+        .items
+        .iter()
+        .filter_map(|impl_item| match impl_item {
+            ImplItem::Method(method) => Some(&method.sig.ident),
+            ImplItem::Const(constant) => Some(&constant.ident),
+            _ => None,
+        })
+        .collect();
+
+    let mut accepted: Vec<Generated> = Vec::with_capacity(generated.len());
+    for gen in generated {
+        let name = &gen.method.sig.ident;
+        // Methods of an extension trait or a builder only conflict with each other, and the finishing method
+        let macro_config = &macro_configs[gen.profile_idx];
+        let (trait_, builder) = (&macro_config.trait_, &macro_config.builder);
+        let same_target = |a: &Generated| &macro_configs[a.profile_idx].trait_ == trait_ && &macro_configs[a.profile_idx].builder == builder;
+
+        let conflict = if trait_.is_none() && builder.is_none() && existing.contains(&name) {
+            Some(format!(
+                "chaining method {} generated from {} conflicts with the existing item {} in the impl block",
+                name, gen.origin, name
+            ))
+        } else if builder.is_some() && *name == finish_name(macro_config) {
+            Some(format!("chaining method {} generated from {} conflicts with the finishing method of the builder", name, gen.origin))
+        } else {
+            accepted
+                .iter()
+                .find(|a| &a.method.sig.ident == name && same_target(a))
+                .map(|a| format!("chaining methods generated from {} and {} are both named {}", a.origin, gen.origin, name))
+        };
+
+        match conflict {
+            Some(_) if gen.skip_conflicts => (),
+            Some(err) => Err(err)?,
+            None => accepted.push(gen),
+        }
+    }
+
+    Ok(accepted)
+}
+
+// Marker type parameters of a builder, one per required method, in order.
+fn required_markers(generated: &[Generated], profile_idx: usize) -> Vec<Ident> {
+    let mut markers: Vec<Ident> = Vec::new();
+    for gen in generated.iter().filter(|gen| gen.profile_idx == profile_idx && gen.required) {
+        let marker = marker_ident(&gen.origin);
+        if !markers.contains(&marker) {
+            markers.push(marker);
+        }
+    }
+    markers
+}
+
+// `__SetUrl` for `set_url`.
+fn marker_ident(origin: &Ident) -> Ident {
+    let name = naming::apply_case(&naming::ident_name(origin), "camel");
+    let mut chars = name.chars();
+    let name: String = chars.next().into_iter().flat_map(char::to_uppercase).chain(chars).collect();
+    Ident::new(&format!("__{}", name), Span::call_site())
+}
+
+// Chaining methods generated from a required method return the builder with its marker set.
+fn apply_typestate(impl_block: &ItemImpl, macro_configs: &[MacroConfig], markers: &[Vec<Ident>], generated: &mut [Generated]) {
+    let args = &t::generic_args(&impl_block.generics);
+    for gen in generated.iter_mut().filter(|gen| gen.required) {
+        let (builder, markers) = match &macro_configs[gen.profile_idx].builder {
+            Some(builder) => (Ident::new(builder, Span::call_site()), &markers[gen.profile_idx]),
+            None => continue,
+        };
+        let set = Ident::new(&format!("{}Set", builder), Span::call_site());
+        let marker = marker_ident(&gen.origin);
+        let state = markers.iter().map(|m| if *m == marker { &set } else { m });
+        gen.method.sig.decl.output = parse_quote! { -> #builder <#(#args,)* #(#state),*> };
+
+        // Methods returning `self` after calling the chain-able method, others forward to those
+        let returns_self = match gen.method.block.stmts.last() {
+            Some(Stmt::Expr(Expr::Path(ExprPath { qself: None, path, .. }))) => *path == parse_quote! { self },
+            _ => false,
+        };
+        if returns_self {
+            gen.method.block.stmts.pop();
+            gen.method.block.stmts.push(Stmt::Expr(parse_quote! { #builder(self.0, ::std::marker::PhantomData) }));
+        }
+    }
+}
+
+fn finish_name(macro_config: &MacroConfig) -> &str {
+    macro_config.finish.as_ref().map_or("build", |finish| finish)
+}
+
+// The builder type of a builder profile, wrapping the type. Its chaining methods are
+// generated like other blocks, only the type, its finishing method, and conversions are here.
+fn gen_builder(impl_block: &ItemImpl, builder: &str, macro_config: &MacroConfig, markers: &[Ident]) -> Result<TokenStream2, String> {
+    let builder_ident = Ident::new(builder, Span::call_site());
+    let vis: Visibility = match &macro_config.vis {
+        Some(vis) => syn::parse_str(vis).map_err(|_| format!("invalid vis attribute: {:?}", vis))?,
+        None => parse_quote! { pub },
+    };
+    let cfg_attrs = &match &macro_config.cfg {
+        Some(cfg) => m::cfg_attrs(cfg, macro_config.doc_cfg)?,
+        None => Vec::new(),
+    };
+
+    let ty = &impl_block.self_ty;
+    let ty_str = t::ty_str(ty, macro_config.type_generics);
+    let ty_link = match t::ty_link_path(ty) {
+        Some(link_path) => format!("\n\n [`{}`]: {}", ty_str, link_path),
+        None => String::new(),
+    };
+    let doc = if macro_config.builder_default {
+        format!(
+            "A builder of [`{0}`], only exposing its chaining methods.\n\n\
+             Created from a [`{0}`] with `From`, or with `Default`.",
+            ty_str
+        )
+    } else {
+        format!(
+            "A builder of [`{0}`], only exposing its chaining methods.\n\n\
+             Created from a [`{0}`] with `From`.",
+            ty_str
+        )
+    };
+
+    let finish_ident = naming::make_ident(finish_name(macro_config))?;
+    let (finish_doc, finish_ret, finish_body) = match (&macro_config.validate, &macro_config.validate_error) {
+        (Some(validate), Some(validate_error)) => {
+            let validate: ExprPath = syn::parse_str(validate).map_err(|e| e.to_string())?;
+            let validate_error: Type = syn::parse_str(validate_error).map_err(|e| e.to_string())?;
+            // `Self` refers to the type, not the builder
+            let validate = match validate.path.segments.first().map(|s| s.into_value().ident.to_string()) {
+                Some(ref first) if first == "Self" && validate.qself.is_none() => {
+                    let rest = validate.path.segments.iter().skip(1);
+                    quote! { <#ty>#(::#rest)* }
+                },
+                _ => quote! { #validate },
+            };
+            (
+                format!("Finish building, returning the [`{}`] if it's valid.{}", ty_str, ty_link),
+                quote! { ::std::result::Result<#ty, #validate_error> },
+                quote! { #validate(&self.0)?; ::std::result::Result::Ok(self.0) },
+            )
+        },
+        _ => (format!("Finish building, returning the [`{}`].{}", ty_str, ty_link), quote! { #ty }, quote! { self.0 }),
+    };
+
+    let generics = &impl_block.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut default_generics = generics.clone();
+    default_generics.make_where_clause().predicates.push(parse_quote! { #ty: ::std::default::Default });
+    let default_where_clause = &default_generics.where_clause;
+
+    if markers.is_empty() {
+        let doc = doc + &ty_link;
+        // Opt-in, the bound is rejected for non-generic types not implementing `Default`
+        let default_impl = if macro_config.builder_default {
+            quote! {
+                #(#cfg_attrs)*
+                impl #impl_generics ::std::default::Default for #builder_ident #ty_generics #default_where_clause {
+                    fn default() -> Self {
+                        #builder_ident(::std::default::Default::default())
+                    }
+                }
+            }
+        } else {
+            TokenStream2::new()
+        };
+        return Ok(quote! {
+            #[doc = #doc]
+            #(#cfg_attrs)*
+            #vis struct #builder_ident #generics (#ty) #where_clause;
+
+            #(#cfg_attrs)*
+            impl #impl_generics #builder_ident #ty_generics #where_clause {
+                #[doc = #finish_doc]
+                #vis fn #finish_ident(self) -> #finish_ret {
+                    #finish_body
+                }
+            }
+
+            #(#cfg_attrs)*
+            impl #impl_generics ::std::convert::From<#ty> for #builder_ident #ty_generics #where_clause {
+                fn from(value: #ty) -> Self {
+                    #builder_ident(value)
+                }
+            }
+
+            #default_impl
+        });
+    }
+
+    // Typestate builder. The builder starts with all markers missing, and can only finish
+    // with all of them set.
+    let set = Ident::new(&format!("{}Set", builder), Span::call_site());
+    let missing = Ident::new(&format!("{}Missing", builder), Span::call_site());
+    let required_names: Vec<String> = markers.iter().map(|marker| format!("`{}`", marker)).collect();
+    let doc = format!(
+        "{}\n\nType parameters {} track whether required methods were called, and are either \
+         [`{}`] or [`{}`]. The finishing method is only available when all of them are set.{}",
+        doc,
+        required_names.join(", "),
+        set,
+        missing,
+        ty_link
+    );
+    let set_doc = format!("A required method of [`{}`] was called.", builder);
+    let missing_doc = format!("A required method of [`{}`] wasn't called yet.", builder);
+
+    let mut struct_generics = generics.clone();
+    struct_generics
+        .params
+        .extend(markers.iter().map(|marker| -> GenericParam { parse_quote! { #marker = #missing } }));
+    let args = &t::generic_args(generics);
+    let all_set = markers.iter().map(|_| &set);
+    let all_missing = markers.iter().map(|_| &missing);
+    let set_ty = quote! { #builder_ident <#(#args,)* #(#all_set),*> };
+    let missing_ty = quote! { #builder_ident <#(#args,)* #(#all_missing),*> };
+    let default_impl = if macro_config.builder_default {
+        quote! {
+            #(#cfg_attrs)*
+            impl #impl_generics ::std::default::Default for #missing_ty #default_where_clause {
+                fn default() -> Self {
+                    #builder_ident(::std::default::Default::default(), ::std::marker::PhantomData)
+                }
+            }
+        }
+    } else {
+        TokenStream2::new()
+    };
+
+    Ok(quote! {
+        #[doc = #set_doc]
+        #(#cfg_attrs)*
+        #vis struct #set;
+
+        #[doc = #missing_doc]
+        #(#cfg_attrs)*
+        #vis struct #missing;
+
+        #[doc = #doc]
+        #(#cfg_attrs)*
+        #vis struct #builder_ident #struct_generics (#ty, ::std::marker::PhantomData<(#(#markers,)*)>) #where_clause;
+
+        #(#cfg_attrs)*
+        impl #impl_generics #set_ty #where_clause {
+            #[doc = #finish_doc]
+            #vis fn #finish_ident(self) -> #finish_ret {
+                #finish_body
+            }
+        }
+
+        #(#cfg_attrs)*
+        impl #impl_generics ::std::convert::From<#ty> for #missing_ty #where_clause {
+            fn from(value: #ty) -> Self {
+                #builder_ident(value, ::std::marker::PhantomData)
+            }
+        }
+
+        #default_impl
+    })
+}
+
+fn gen_fluent_inblock(impl_block: &ItemImpl, generated: &[Generated]) -> ItemImpl {
+    let mut inblock_impl_block = impl_block.clone();
+    inblock_impl_block.items = Vec::with_capacity(impl_block.items.len() + generated.len());
+
+    for (pos, impl_item) in impl_block.items.iter().enumerate() {
+        inblock_impl_block.items.push(impl_item.clone());
+        for gen in generated.iter().filter(|gen| gen.inblock && gen.pos == pos) {
+            inblock_impl_block.items.push(ImplItem::Method(gen.method.clone()));
+        }
+    }
+
+    inblock_impl_block
+}
+
+fn gen_fluent_new_block(impl_block: &ItemImpl, generated: &[Generated], profile_idx: usize, group: Option<&String>) -> ItemImpl {
+    let mut new_impl_block = impl_block.clone();
+    new_impl_block.items = generated
+        .iter()
+        .filter(|gen| !gen.inblock && gen.profile_idx == profile_idx && gen.group.as_ref() == group)
+        .map(|gen| ImplItem::Method(gen.method.clone()))
+        .collect();
+    new_impl_block
+}
+
+// Turn a block of chaining methods into an extension trait and its impl for the type.
+// The trait is public unless the block-level `vis` is set.
+fn gen_fluent_trait(
+    impl_block: ItemImpl,
+    trait_: &str,
+    vis: Option<&String>,
+    doc: &str,
+    cfg_attrs: &[Attribute],
+) -> Result<TokenStream2, String> {
+    let trait_ident = Ident::new(trait_, Span::call_site());
+    let trait_vis: Visibility = match vis {
+        Some(vis) => syn::parse_str(vis).map_err(|_| format!("invalid vis attribute: {:?}", vis))?,
+        None => parse_quote! { pub },
+    };
+    let trait_items: Vec<TraitItem> = impl_block
+        .items
+        .iter()
+        .filter_map(|impl_item| match impl_item {
+            ImplItem::Method(method) => Some(TraitItem::Method(m::fluent_trait_decl(method))),
+            _ => None,
+        })
+        .collect();
+
+    let mut trait_impl_block = impl_block;
+    trait_impl_block.attrs.extend(cfg_attrs.iter().cloned());
+    for impl_item in &mut trait_impl_block.items {
+        if let ImplItem::Method(method) = impl_item {
+            method.vis = Visibility::Inherited;
+            // Those are on the trait method declaration
+            method.attrs.retain(|a| {
+                a.path != parse_quote! { doc } && a.path != parse_quote! { must_use } && a.path != parse_quote! { deprecated }
+            });
+        }
+    }
+
+    let (_, ty_generics, _) = trait_impl_block.generics.split_for_impl();
+    trait_impl_block.trait_ = Some((None, parse_quote! { #trait_ident #ty_generics }, Token!(for)(Span::call_site())));
+
+    let generics = &trait_impl_block.generics;
+    let where_clause = &generics.where_clause;
+    Ok(quote! {
+        #[doc = #doc]
+        #(#cfg_attrs)*
+        #trait_vis trait #trait_ident #generics #where_clause {
+            #(#trait_items)*
+        }
+
+        #trait_impl_block
+    })
 }
@@ -19,6 +19,8 @@
 //! Chain-able methods are the ones with `&mut self` as a first argument, and return nothing.
 //! That's it, there are no other restrictions.
 //!
+//! `#[fluent_impl]` can also be applied to a single chain-able method in an impl block.
+//!
 //! # Usage
 //! Add `fluent-impl` to the dependencies in `Cargo.toml`:
 //!
@@ -90,7 +92,12 @@
 //! itself, and options passed to a method-level attribute `#[fluent_impl_opts]`.
 //!
 //! ## `#[fluent_impl]` Attribute Options
-//! *(`inblock`, `non_public`, `prefix`, `impl_doc`, `doc`)*
+//! *(`inblock`, `non_public`, `include`, `exclude`, `strict`, `skip_conflicts`, `vis`, `hidden`,
+//! `forward_attrs`, `drop_attrs`, `add_attrs`, `cfg`, `doc_cfg`, `prefix`, `suffix`, `name_template`,
+//! `strip_prefix`, `case`, `deprecated_prefix`, `if_variant`, `opt_variant`, `each`, `setters`,
+//! `impl_doc`, `doc`, `inherit_doc`, `doc_alias`, `see_also`, `type_generics`, `doc_example`,
+//! `doc_example_init`, `profile`, `trait`, `builder`, `builder_default`, `finish`, `validate`,
+//! `validate_error`, `groups`, `preset`)*
 //!
 //!  *impl block*-level configuration.
 //!
@@ -125,40 +132,409 @@
 //!    There is a corresponding method-level *`non_public`* option which will selectively enable
 //!    this behavior for individual methods.
 //!
+//!  * **`include`**, **`exclude`** (default: unset)
+//!
+//!    Comma-separated method name patterns limiting the methods chaining methods are generated
+//!    from, where `*` matches any sequence of characters and `?` matches a single character.
+//!    Patterns without wildcards are method names. If *`include`* is set, only matching
+//!    methods are considered. Methods matching *`exclude`* are skipped.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl(include = "set_*, add_*", exclude = "*_raw, debug_*")]
+//!    impl Simple {
+//!        // ...
+//!    }
+//!    ```
+//!
+//!    Skipped methods are not checked with the rules above (e.g. *`non_public`*), and their
+//!    `#[fluent_impl_opts]` options are ignored.
+//!
+//!  * **`strict`** (default: unset)
+//!
+//!    Every *`include`* and *`exclude`* pattern must match a method in the impl block.
+//!
+//!  * **`skip_conflicts`** (default: unset)
+//!
+//!    Generated chaining method names are checked against each other, and against the names
+//!    of items in the impl block. By default, a conflict is an error naming both methods.
+//!    If this option is passed, a chaining method with a conflicting name is not generated
+//!    instead.
+//!
+//!    Items in other impl blocks of the type are not checked.
+//!
+//!    There is a corresponding method-level *`skip_conflicts`* option which will selectively
+//!    enable this behavior for individual methods.
+//!
+//!  * **`vis`** (default: unset)
+//!
+//!    By default, a chaining method has the same visibility as the chain-able one. If this
+//!    option is set (e.g. `vis = "pub(crate)"`), chaining methods get this visibility instead.
+//!    If *`trait`* is set, this is the visibility of the extension trait.
+//!
+//!    A chaining method may end up more visible than the types in its arguments. That's
+//!    checked, and is an error if an argument type doesn't depend on generic parameters.
+//!
+//!    There is a corresponding method-level *`vis`* option which will selectively override
+//!    the value set here.
+//!
+//!  * **`hidden`** (default: unset)
+//!
+//!    Hide chaining methods from docs with `#[doc(hidden)]`.
+//!
+//!    There is a corresponding method-level *`hidden`* option which will selectively enable
+//!    this behavior for individual methods.
+//!
+//!  * **`forward_attrs`** (default: unset)
+//!
+//!    Only some attributes of a chain-able method are forwarded to its chaining method:
+//!    `cfg`, `deprecated`, `inline`, `track_caller` and `doc(cfg(...))`. Attributes like
+//!    `#[test]` or `#[export_name]` are not. This option is a comma-separated list of
+//!    additional attribute paths to forward, e.g. `forward_attrs = "allow, cfg_attr"`.
+//!
+//!    Chaining methods also get `#[must_use]`, since they return the value they modified,
+//!    and `#[allow(deprecated)]` if the chain-able method is deprecated.
+//!
+//!  * **`drop_attrs`** (default: unset)
+//!
+//!    A comma-separated list of attribute paths not to forward, e.g. `drop_attrs = "inline"`.
+//!    Pass `must_use` to not add `#[must_use]`.
+//!
+//!  * **`add_attrs`** (default: unset)
+//!
+//!    Attributes added to every chaining method, e.g. `add_attrs = "#[inline] #[cold]"`.
+//!
+//!    Method-level *`forward_attrs`*, *`drop_attrs`* and *`add_attrs`* options are combined
+//!    with the ones set here.
+//!
+//!  * **`cfg`** (default: unset)
+//!
+//!    A cfg predicate gating the generated chaining methods (and the generated impl block
+//!    or extension trait), e.g. `cfg = "feature = \"fluent\""`. "See also" links added
+//!    by *`see_also`* are gated too.
+//!
+//!    There is a corresponding method-level *`cfg`* option. Both predicates apply if both
+//!    are set.
+//!
+//!  * **`doc_cfg`** (default: unset)
+//!
+//!    Also add `#[cfg_attr(docsrs, doc(cfg(...)))]` with the *`cfg`* predicate, so docs built
+//!    with `--cfg docsrs` (e.g. on docs.rs) show the gating. This requires
+//!    `#![cfg_attr(docsrs, feature(doc_cfg))]` in the crate using the macro.
+//!
+//!    There is a corresponding method-level *`doc_cfg`* option which will selectively enable
+//!    this behavior for the method-level *`cfg`*.
+//!
 //!  * **`prefix`** (default: "with_")
 //!
 //!    The default chaining method name is this prefix appended by the chain-able method name.
 //!
-//!    * *`prefix`* is not allowed to be an empty string. Check the *`name`* method-level option
-//!    if you want to name a chaining method to whatever you like.
+//!    * *`prefix`* is allowed to be an empty string, but the chaining method name is not allowed
+//!      to be the same as the chain-able one. Check the *`name`* method-level option if you want
+//!      to name a chaining method to whatever you like.
 //!
 //!    There is a corresponding method-level *`prefix`* option which will selectively override
 //!    the value set here (or the default).
 //!
+//!  * **`suffix`** (default: "")
+//!
+//!    Appended to the chain-able method name after the prefix.
+//!
+//!    There is a corresponding method-level *`suffix`* option which will selectively override
+//!    the value set here (or the default).
+//!
+//!  * **`name_template`** (default: unset)
+//!
+//!    A template for the chaining method name, where `{}` is replaced with the chain-able
+//!    method name. e.g. `name_template = "with_{}_applied"`. If set, *`prefix`* and
+//!    *`suffix`* are ignored.
+//!
+//!    There is a corresponding method-level *`name_template`* option which will selectively
+//!    override the value set here. A method-level *`prefix`* or *`suffix`* also overrides it.
+//!
+//!  * **`strip_prefix`** (default: unset)
+//!
+//!    Strip this prefix from chain-able method names before generating chaining method names,
+//!    if present. e.g. with `strip_prefix = "set_"`, `set_color` generates `with_color`,
+//!    or `color` if *`prefix`* is an empty string.
+//!
+//!    There is a corresponding method-level *`strip_prefix`* option which will selectively
+//!    override the value set here.
+//!
+//!  * **`case`** (default: unset)
+//!
+//!    Convert the case of the chain-able method name (after *`strip_prefix`*) before generating
+//!    chaining method names. One of `"snake"`, `"camel"`, `"lower"` or `"upper"`.
+//!
+//!    There is a corresponding method-level *`case`* option which will selectively override
+//!    the value set here.
+//!
+//!    Generated names that are keywords are turned into raw identifiers (e.g. `r#type`).
+//!    Chain-able methods with raw identifier names are supported, the `r#` is not a part of
+//!    the name used for generation.
+//!
+//!  * **`deprecated_prefix`** (default: unset)
+//!
+//!    Additionally generate deprecated chaining methods named with this prefix instead
+//!    of *`prefix`*, forwarding to the chaining methods. This allows staging a change of
+//!    *`prefix`* without breaking users of the old names at once.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl(prefix = "and_", deprecated_prefix = "with_")]
+//!    impl Simple {
+//!        // generates `and_add_1()`, and a deprecated `with_add_1()`
+//!        pub fn add_1(&mut self) {
+//!            // ...
+//!        }
+//!    }
+//!    ```
+//!
+//!    Only chaining methods named with the block-level *`prefix`* get a deprecated alias.
+//!    Also check the *`deprecated_alias`* method-level option.
+//!
+//!  * **`if_variant`** (default: unset)
+//!
+//!    Additionally generate a conditional variant of every chaining method, named with an
+//!    `_if` suffix, taking a `bool` before the other arguments. The chain-able method is only
+//!    called if it's `true`.
+//!
+//!    ``` rust ignore
+//!    let s = Simple::default().with_add_flag_if(verbose, "verbose");
+//!    ```
+//!
+//!    There is a corresponding method-level *`if_variant`* option which will selectively enable
+//!    this behavior for individual methods.
+//!
+//!  * **`opt_variant`** (default: unset)
+//!
+//!    Additionally generate a variant of every chaining method with arguments, named with an
+//!    `_opt` suffix, taking the arguments in an `Option` (a tuple of them, if there are more
+//!    than one). The chain-able method is only called on `Some`.
+//!
+//!    ``` rust ignore
+//!    let s = Simple::default().with_set_port_opt(config.port).with_add_flag_opt(Some(("v", 1)));
+//!    ```
+//!
+//!    There is a corresponding method-level *`opt_variant`* option which will selectively enable
+//!    this behavior for individual methods.
+//!
+//!  * **`each`** (default: unset)
+//!
+//!    Additionally generate a variant of every chaining method with arguments, taking an
+//!    `impl IntoIterator` of them, and calling the chaining method with every item. Items of
+//!    methods with multiple arguments are tuples. The variant name is the chaining method name
+//!    followed by `s`, or the passed template, where `{}` is replaced with the chaining method
+//!    name (e.g. `each = "{}_all"`).
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl(each)]
+//!    impl Simple {
+//!        // Also generates with_add_headers(self, hs: impl IntoIterator<Item = Header>)
+//!        pub fn add_header(&mut self, h: Header) {
+//!            // ...
+//!        }
+//!    }
+//!    ```
+//!
+//!    There is a corresponding method-level *`each`* option, which also takes a name.
+//!
+//!  * **`setters`** (default: unset)
+//!
+//!    Generate setter chaining methods from all accessors named with a `_mut` suffix, as if
+//!    the method-level *`setter`* option is set for them. Accessors with the method-level
+//!    *`nested`* option set are not affected.
+//!
 //!  * **`impl_doc`** (default: "Chaining (fluent) methods for [\`%t%\`].")
 //!
 //!    If a new block is generated for the chaining methods, this is the doc string template
-//!    for it. `%t%` is replaced with the type path.
+//!    for it. `%t%` is replaced with the type name (see *`type_generics`*), and a link
+//!    definition for ``[`%t%`]`` is appended.
 //!
 //!  * **`doc`** (default: "The chaining (fluent) equivalent of [\`%f%()\`].")
 //!
-//!    Chaining method doc string template. `%t%` is replaced with the type path. `%f%` is
-//!    replaced with the chain-able method name.
+//!    Chaining method doc string template. The following placeholders are replaced:
+//!    * `%t%`: the type name, i.e. the last segment of the type path (see *`type_generics`*).
+//!    * `%f%`: the chain-able method name.
+//!    * `%n%`: the chaining method name.
+//!    * `%a%`: the argument list of the chain-able method, without the receiver
+//!      (e.g. `step: i32, other: &Self`).
+//!    * `%r%`: the receiver of the chain-able method (e.g. `&mut self`).
+//!    * `%d%`: the doc comment of the chain-able method.
 //!
 //!    Additionally, the following is effectively appended at the end:
 //!    ``` text
 //!     ///
-//!     /// [`%f%`]: %t%::%f%
-//!     /// [`%f%()`]: %t%::%f%
+//!     /// [`%t%`]: Self
+//!     /// [`%f%`]: Self::%f%
+//!     /// [`%f%()`]: Self::%f%
 //!    ```
 //!
-//!    This allows proper hyper-linking of ``[`%t%`]`` and ``[`%t%()`]``.
+//!    This allows proper hyper-linking of ``[`%t%`]``, ``[`%f%`]`` and ``[`%f%()`]``.
+//!    If *`trait`* is set, the type path (without generic arguments) is used instead of `Self`.
 //!
 //!    There is a corresponding method-level *`doc`* option which will selectively override
 //!    the value set here (or the default).
 //!
+//!  * **`inherit_doc`** (default: unset)
+//!
+//!    Use the doc comment of the chain-able method for the chaining method, followed by
+//!    a one-line note linking to the chain-able method. This takes precedence over *`doc`*.
+//!
+//!    There is a corresponding method-level *`inherit_doc`* option which will selectively enable
+//!    this behavior for individual methods.
+//!
+//!  * **`doc_alias`** (default: unset)
+//!
+//!    Add a `#[doc(alias = "...")]` with the chain-able method name to chaining methods,
+//!    so searching docs for e.g. `add_1` finds `with_add_1()` too.
+//!
+//!    There is a corresponding method-level *`doc_alias`* option which will selectively enable
+//!    this behavior for individual methods.
+//!
+//!  * **`see_also`** (default: unset)
+//!
+//!    Append a "See also: [\`with_add_1()\`]" link to the doc comment of the chain-able method,
+//!    pointing to the chaining method.
+//!
+//!    There is a corresponding method-level *`see_also`* option which will selectively enable
+//!    this behavior for individual methods.
+//!
+//!  * **`type_generics`** (default: unset)
+//!
+//!    Show the generic arguments of the type in `%t%` (e.g. `Wrapper<T>` instead of `Wrapper`).
+//!
+//!  * **`doc_example`** (default: unset)
+//!
+//!    Append a usage example to the docs of chaining methods, showing the chaining method
+//!    next to the chain-able one:
+//!
+//!    ``` rust ignore
+//!    let mut simple = Simple::default();
+//!    simple.add(step);
+//!
+//!    // The same, chained
+//!    let simple = Simple::default().with_add(step);
+//!    ```
+//!
+//!    The example code block is marked `ignore` if this option is passed as a word. It can
+//!    also be set to `"ignore"` or `"no_run"`. `no_run` examples are compiled as doc tests,
+//!    with hidden lines declaring the arguments. So *`doc_example_init`* should be set to an
+//!    expression that resolves in doc tests.
+//!
+//!    There is a corresponding method-level *`doc_example`* option which will selectively
+//!    override the value set here.
+//!
+//!  * **`doc_example_init`** (default: "%t%::default()")
+//!
+//!    The expression creating the value in usage examples. `%t%` is replaced with the type
+//!    name.
+//!
+//!    There is a corresponding method-level *`doc_example_init`* option which will selectively
+//!    override the value set here.
+//!
+//!  * **`profile`** (default: unset)
+//!
+//!    `#[fluent_impl]` can be applied to the same impl block more than once. Every
+//!    application is a separate *profile* generating its own family of chaining methods,
+//!    with its own options. This option names the profile, and is required if more
+//!    than one profile is used.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl(profile = "owned")]
+//!    #[fluent_impl(profile = "ext", prefix = "and_", trait = "SimpleExt")]
+//!    impl Simple {
+//!        // ...
+//!    }
+//!    ```
+//!
+//!    There is a corresponding method-level *`profile`* option which restricts
+//!    `#[fluent_impl_opts]` options to one profile.
+//!
+//!  * **`trait`** (default: unset)
+//!
+//!    Instead of a new impl block, generate an extension trait with this name
+//!    containing the chaining methods, and implement it for the type. The trait has the
+//!    same generic parameters as the impl block, and is documented with *`impl_doc`*.
+//!    The trait is public unless *`vis`* is set.
+//!
+//!    * *`trait`* is not allowed to be set if *`inblock`* is set. The method-level
+//!      *`inblock`* option is ignored for trait profiles.
+//!
+//!  * **`builder`** (default: unset)
+//!
+//!    Instead of a new impl block, generate a builder type with this name wrapping the type,
+//!    and put the chaining methods there. Only chaining methods are exposed by the builder,
+//!    they keep the names of chain-able methods if *`prefix`* is empty. The builder is created
+//!    with `From` (or `Default`, see *`builder_default`*), and is public unless *`vis`* is set.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl(builder = "ServerBuilder", builder_default, validate = "Self::validate", validate_error = "String")]
+//!    impl Server {
+//!        pub fn set_port(&mut self, port: u16) {
+//!            // ...
+//!        }
+//!
+//!        fn validate(&self) -> Result<(), String> {
+//!            // ...
+//!        }
+//!    }
+//!
+//!    let server: Result<Server, String> = ServerBuilder::default().with_set_port(80).build();
+//!    ```
+//!
+//!    * *`builder`* is not allowed to be set if *`trait`* or *`inblock`* is set. The method-level
+//!      *`inblock`* option is ignored for builder profiles, and doc examples are not generated.
+//!    * `Self` in argument types and method generics of builder methods still refers to the
+//!      type, not the builder.
+//!    * *`builder_default`*, *`finish`*, *`validate`* and *`validate_error`* are not allowed to be
+//!      set if *`builder`* is not set.
+//!
+//!  * **`builder_default`** (default: unset)
+//!
+//!    Also implement `Default` for the builder, wrapping the default value of the type. The type
+//!    must implement `Default`, unless the impl block is generic, where the impl is bounded on it.
+//!
+//!  * **`finish`** (default: `"build"`)
+//!
+//!    The name of the builder method returning the built value.
+//!
+//!  * **`validate`**, **`validate_error`** (default: unset)
+//!
+//!    A function (or method) path checking the built value, taking `&T` and returning
+//!    `Result<(), E>`, and the error type returned by the finishing method. `Self` in the path
+//!    is the type, not the builder. If set, the finishing method returns `Result<T, E>`.
+//!    Both must be set together.
+//!
+//!  * **`groups`** (default: unset)
+//!
+//!    Chaining methods can be split into several generated impl blocks, each with its own
+//!    doc string. This option lists the groups with their doc string templates, where `%t%`
+//!    is replaced like in *`impl_doc`*. Methods are assigned to groups with the method-level
+//!    *`group`* option. Ungrouped methods stay in the block documented with *`impl_doc`*.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl(groups(Network = "Network configuration.", Math = "Arithmetic."))]
+//!    impl Simple {
+//!        #[fluent_impl_opts(group = "Network")]
+//!        pub fn set_port(&mut self, port: u16) {
+//!            // ...
+//!        }
+//!    }
+//!    ```
+//!
+//!    Generated blocks appear in the order of groups. Groups don't apply to *`inblock`*
+//!    methods and trait profiles, but the groups of their methods must still be listed.
+//!
+//!  * **`preset`** (default: unset)
+//!
+//!    Use a named preset from the crate manifest (see *Crate-wide Defaults And Presets*
+//!    below). Options passed to the attribute override the ones set by the preset.
+//!
 //! ## `#[fluent_impl_opts]` Attribute Options
-//! *(`inblock`, `non_public`, `skip`, `prefix`, `rename`, `name`, `doc`)*
+//! *(`inblock`, `non_public`, `skip_conflicts`, `vis`, `hidden`, `forward_attrs`, `drop_attrs`,
+//! `add_attrs`, `cfg`, `doc_cfg`, `if_variant`, `opt_variant`, `each`, `skip`, `prefix`, `suffix`,
+//! `name_template`, `strip_prefix`, `case`, `rename`, `name`, `deprecated_alias`, `flag`, `preset`,
+//! `nested`, `nested_chain`, `setter`, `setter_into`, `required`, `extend`, `from_iter`, `op`, `doc`,
+//! `inherit_doc`, `doc_alias`, `see_also`, `doc_example`, `doc_example_init`, `profile`, `group`)*
 //!
 //! Options passed to override block-level defaults, or set method-specific
 //! configurations.
@@ -195,17 +571,74 @@
 //!    This allows generating chaining methods for specific private methods, or
 //!    partially public ones (e.g. `pub(crate)` methods).
 //!
+//!  * **`skip_conflicts`** (default: inherit)
+//!
+//!    Set *`skip_conflicts`* for this specific method if it's not set for the block already.
+//!
+//!  * **`vis`** (default: inherit)
+//!
+//!    Override the block value if set. Setting *`vis`* for a method implies *`non_public`*,
+//!    so a public chaining method can be generated for a private mutator.
+//!
+//!    This has no effect for trait profiles.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl_opts(vis = "pub")]
+//!    fn add_1(&mut self) {
+//!        // ...
+//!    }
+//!    ```
+//!
+//!  * **`hidden`** (default: inherit)
+//!
+//!    Set *`hidden`* for this specific method if it's not set for the block already.
+//!
+//!  * **`forward_attrs`**, **`drop_attrs`**, **`add_attrs`** (default: inherit)
+//!
+//!    Combined with the block values if set.
+//!
+//!  * **`if_variant`**, **`opt_variant`** (default: inherit)
+//!
+//!    Set these options for this specific method if they are not set for the block already.
+//!
+//!  * **`each`** (default: inherit)
+//!
+//!    Set *`each`* for this specific method if it's not set for the block already. A name can be
+//!    passed (e.g. `each = "with_headers"`), overriding the block-level template.
+//!
+//!  * **`cfg`**, **`doc_cfg`** (default: unset)
+//!
+//!    Gate this chaining method with a cfg predicate, in addition to the block-level *`cfg`*.
+//!
 //!  * **`prefix`** (default: inherit)
 //!
 //!    Override the default, or the block value if set.
 //!
-//!    * *`prefix`* is not allowed to be an empty string.
 //!    * Method-specific *`prefix`* is not allowed to be set if *`name`*(see below) is set.
 //!
-//!  * **`doc`** (default: inherit)
+//!  * **`suffix`**, **`name_template`**, **`strip_prefix`**, **`case`** (default: inherit)
 //!
 //!    Override the default, or the block value if set.
 //!
+//!    * These options are not allowed to be set if *`name`*(see below) is set.
+//!    * Method-specific *`prefix`* or *`suffix`* are not allowed to be set if method-specific
+//!      *`name_template`* is set.
+//!
+//!  * **`doc`** (default: inherit)
+//!
+//!    Override the default, or the block value if set. This also takes precedence over
+//!    a block-level *`inherit_doc`*.
+//!
+//!  * **`inherit_doc`**, **`doc_alias`**, **`see_also`** (default: inherit)
+//!
+//!    Set these options for this specific method if they are not set for the block already.
+//!
+//!    * Method-specific *`inherit_doc`* is not allowed to be set if method-specific *`doc`* is set.
+//!
+//!  * **`doc_example`**, **`doc_example_init`** (default: inherit)
+//!
+//!    Override the block value if set.
+//!
 //!  #### Method Specific
 //!
 //!  * **`skip`** (default: unset)
@@ -222,10 +655,363 @@
 //!
 //!  * **`name`** (default: unset)
 //!
-//!    Set the name of the chaining method.
+//!    Set the name of the chaining method. Keywords are turned into raw identifiers
+//!    (e.g. `name = "type"` generates `r#type`).
 //!
 //!    * *`name`* is not allowed to be set if method-specific *`prefix`* or *`rename`* is set.
 //!
+//!  * **`deprecated_alias`** (default: unset)
+//!
+//!    Comma-separated old names of the chaining method. A deprecated chaining method
+//!    forwarding to the chaining method is generated for each of them, with a deprecation
+//!    note pointing to the new name.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl_opts(rename = "added_1", deprecated_alias = "with_add_1")]
+//!    pub fn add_1(&mut self) {
+//!        // ...
+//!    }
+//!    ```
+//!
+//!  * **`flag`** (default: unset)
+//!
+//!    For a method taking a single `bool`, generate a pair of chaining methods taking
+//!    nothing instead: the chaining method passing `true`, and an off method passing `false`.
+//!    The off method name replaces the `with_` prefix of the chaining method name with
+//!    `without_`. Both names can be set with `flag(on = "...", off = "...")`.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl(strip_prefix = "set_")]
+//!    impl Simple {
+//!        // with_enabled() and without_enabled()
+//!        #[fluent_impl_opts(flag)]
+//!        pub fn set_enabled(&mut self, on: bool) {
+//!            // ...
+//!        }
+//!
+//!        // verbose() and quiet()
+//!        #[fluent_impl_opts(flag(on = "verbose", off = "quiet"))]
+//!        pub fn set_verbose(&mut self, on: bool) {
+//!            // ...
+//!        }
+//!    }
+//!    ```
+//!
+//!    * *`flag(on = ...)`* sets *`name`*, the same rules apply.
+//!
+//!  * **`preset`** (default: unset)
+//!
+//!    Generate a chaining method taking nothing, forwarding fixed arguments to the chaining
+//!    method. This list option can be passed multiple times.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl_opts(preset(name = "with_red", args = "Color::Red"))]
+//!    #[fluent_impl_opts(preset(name = "with_gray", args = "Color::Rgb(128, 128, 128)"))]
+//!    pub fn set_color(&mut self, color: Color) {
+//!        // ...
+//!    }
+//!    ```
+//!
+//!    * *`args`* are the arguments of the chaining method, after parameter options are
+//!      applied (see *`#[fluent]` Parameter Options* below).
+//!
+//!  * **`nested`**, **`nested_chain`** (default: unset)
+//!
+//!    Generate a chaining method from an accessor returning a mutable reference to a child
+//!    (`fn child_mut(&mut self) -> &mut T`), taking a closure configuring the child. A `_mut`
+//!    suffix is stripped from the chaining method name.
+//!
+//!    *`nested_chain`* additionally generates a `_chain` method, taking a closure which takes
+//!    the child by value and returns it, for children with chaining methods of their own. The
+//!    child is left with its default value while the closure runs, so it must implement
+//!    `Default`.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl_opts(nested_chain)]
+//!    pub fn tls_mut(&mut self) -> &mut TlsConfig {
+//!        &mut self.tls
+//!    }
+//!
+//!    // ...
+//!
+//!    let config = Config::default()
+//!        .with_tls(|tls| tls.set_verify(true))
+//!        .with_tls_chain(|tls| tls.with_set_cert("cert.pem"));
+//!    ```
+//!
+//!    * *`doc_example`* doesn't apply to nested chaining methods.
+//!
+//!  * **`setter`**, **`setter_into`** (default: unset)
+//!
+//!    Generate a chaining method from an accessor returning a mutable reference to a field
+//!    (`fn field_mut(&mut self) -> &mut T`), taking a value to assign to it. *`setter_into`*
+//!    takes `impl Into<T>` instead. A `_mut` suffix is stripped from the chaining method name.
+//!
+//!    ``` rust ignore
+//!    // with_timeout(mut self, value: Duration) -> Self
+//!    #[fluent_impl_opts(setter)]
+//!    pub fn timeout_mut(&mut self) -> &mut Duration {
+//!        &mut self.timeout
+//!    }
+//!    ```
+//!
+//!    * *`setter`* is not allowed to be set if *`nested`* is set.
+//!    * *`doc_example`* doesn't apply to setter chaining methods.
+//!
+//!  * **`required`** (default: unset)
+//!
+//!    In builder profiles (see the block-level *`builder`* option), require this method to be
+//!    called before the finishing method. The builder gets a marker type parameter per required
+//!    method, `<Builder>Missing` by default, and the chaining method returns the builder with its
+//!    marker set to `<Builder>Set`. The finishing method is only implemented for the builder with
+//!    all markers set, so a missing call is a compile error.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl(builder = "RequestBuilder", builder_default)]
+//!    impl Request {
+//!        #[fluent_impl_opts(required)]
+//!        pub fn set_url(&mut self, url: &str) {
+//!            // ...
+//!        }
+//!    }
+//!
+//!    // error[E0599]: no method named `build` found for struct `RequestBuilder`
+//!    let request = RequestBuilder::default().build();
+//!    ```
+//!
+//!    * *`required`* is not allowed to be set if *`if_variant`*, *`opt_variant`*, *`each`* or
+//!      *`nested_chain`* is set. Block-level variants are not generated for required methods.
+//!
+//!  * **`extend`**, **`from_iter`** (default: unset)
+//!
+//!    Generate an `Extend` or a `FromIterator` impl for the type, calling this method with
+//!    every item. Items of methods with multiple arguments are tuples. Generic parameters of the
+//!    method are added to the generic parameters of the impl block.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl_opts(extend, from_iter)]
+//!    pub fn push(&mut self, item: String) {
+//!        // ...
+//!    }
+//!    ```
+//!
+//!    * These options are independent of generating the chaining method, and are not allowed
+//!      to target a profile.
+//!    * The impls are gated by the block-level and method-level *`cfg`*, and all profiles must
+//!      have the same block-level *`cfg`*.
+//!    * *`from_iter`* requires the type to implement `Default`.
+//!
+//!  * **`op`** (default: unset)
+//!
+//!    Generate an impl of the passed operator assignment trait (e.g. `"AddAssign"`) for the type,
+//!    calling this single-argument method, and an impl of its by-value counterpart (e.g. `Add`),
+//!    returning the type after calling it. All `*Assign` traits of `std::ops` are supported.
+//!
+//!    ``` rust ignore
+//!    // v += w, and v + w
+//!    #[fluent_impl_opts(op = "AddAssign")]
+//!    pub fn add_assign_vec(&mut self, v: Vec3) {
+//!        // ...
+//!    }
+//!    ```
+//!
+//!    * Like *`extend`* and *`from_iter`*, *`op`* is independent of generating the chaining
+//!      method, is not allowed to target a profile, and its impls are gated by *`cfg`*.
+//!
+//!  * **`profile`** (default: unset)
+//!
+//!    Only apply the other options passed to this `#[fluent_impl_opts]` to the named profile
+//!    (see the block-level *`profile`* option). Options without a profile apply to all
+//!    profiles.
+//!
+//!    ``` rust ignore
+//!    #[fluent_impl_opts(profile = "ext", skip)]
+//!    pub fn add_1(&mut self) {
+//!        // ...
+//!    }
+//!    ```
+//!
+//!  * **`group`** (default: unset)
+//!
+//!    Put this chaining method in the generated block of the named group (see the block-level
+//!    *`groups`* option). The group must be listed in *`groups`*.
+//!
+//! ## `#[fluent]` Parameter Options
+//! *(`into`, `strip_option`, `default`)*
+//!
+//! Options passed to parameters of chain-able methods, changing how the chaining method takes
+//! them. These attributes are removed from the chain-able method.
+//!
+//! ``` rust ignore
+//! #[fluent_impl]
+//! impl Simple {
+//!     pub fn set_name(&mut self, #[fluent(into)] name: String, #[fluent(default = "0")] id: u32) {
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! Generates:
+//!
+//! ``` rust ignore
+//! pub fn with_set_name(mut self, name: impl Into<String>) -> Self {
+//!     self.set_name(name.into(), 0);
+//!     self
+//! }
+//! ```
+//!
+//!  * **`into`** (default: unset)
+//!
+//!    Take `impl Into<T>` instead of `T`, and pass the argument with `.into()`.
+//!
+//!  * **`strip_option`** (default: unset)
+//!
+//!    Take `T` for an `Option<T>` parameter, and pass the argument in `Some`. Combined with
+//!    *`into`*, the chaining method takes `impl Into<T>`.
+//!
+//!  * **`default`** (default: unset)
+//!
+//!    An expression always passed for this parameter. The chaining method doesn't take it.
+//!
+//!    * *`default`* is not allowed to be set if *`into`* or *`strip_option`* is set.
+//!
+//! ## Crate-wide Defaults And Presets
+//!
+//! Block-level options can also be set in the `Cargo.toml` of the crate using the macro,
+//! under `[package.metadata.fluent-impl]`. Options set there are crate-wide defaults.
+//! Named sets of options (presets) can be set in sub-tables of
+//! `[package.metadata.fluent-impl.presets]`, and used with the block-level *`preset`* option.
+//!
+//! ``` toml
+//! [package.metadata.fluent-impl]
+//! non_public = true
+//! prefix = "and_"
+//!
+//! [package.metadata.fluent-impl.presets.builder]
+//! prefix = "with_"
+//! doc = "Builder method, see [`%f%()`]."
+//! ```
+//!
+//! ``` rust ignore
+//! #[fluent_impl(preset = "builder")]
+//! impl Simple {
+//!     // ...
+//! }
+//! ```
+//!
+//! Options with a value take a string, and options without a value take a boolean,
+//! where `false` turns the option off (e.g. in a preset, overriding a manifest default). Lists
+//! like *`groups`* take a table
+//! (e.g. `[package.metadata.fluent-impl.groups]`).
+//!
+//! The order of precedence is: default < manifest < preset < `#[fluent_impl]` < `#[fluent_impl_opts]`.
+//!
+//! Block-level options without a value can also be turned off in `#[fluent_impl]` by setting
+//! them to `false` (e.g. `#[fluent_impl(non_public = false)]`).
+//!
+//! ## `#[fluent_impl]` On A Single Method
+//!
+//! If only one chaining method is needed, `#[fluent_impl]` can be applied directly to a
+//! chain-able method in an impl block instead of the block itself. The method is
+//! kept as is, and its chaining equivalent is generated right below it.
+//!
+//! Options passed to the attribute in this position are the same ones accepted by
+//! `#[fluent_impl_opts]`, and block-level options take their default values (or the
+//! crate-wide defaults from the manifest).
+//! Additional `#[fluent_impl_opts]` attributes may follow `#[fluent_impl]`.
+//!
+//! Unlike the block case, applying the attribute to a method that is not chain-able
+//! (or is skipped) is an error.
+//!
+//! ``` rust ignore
+//! impl Simple {
+//!     #[fluent_impl(rename = "added_1")]
+//!     pub fn add_1(&mut self) {
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! # `#[derive(Fluent)]`
+//!
+//! For plain data structs, chaining methods can be derived from the fields instead of
+//! written as chain-able `&mut self` setters first. Every field gets a `with_<field>(mut self, value)`
+//! method, documented with the field's docs. Fields of tuple structs are named by their index
+//! (e.g. `with_0()`). Generic structs are supported.
+//!
+//! ``` rust ignore
+//! #[derive(Fluent, Default)]
+//! #[fluent(set)]
+//! pub struct Config {
+//!     /// The connection timeout, in seconds.
+//!     timeout: u32,
+//!     #[fluent(strip_option, into)]
+//!     user: Option<String>,
+//!     #[fluent(each = "with_tag")]
+//!     tags: Vec<String>,
+//! }
+//! ```
+//!
+//! Generates (among others):
+//!
+//! ``` rust ignore
+//! /// The connection timeout, in seconds.
+//! #[must_use]
+//! pub fn with_timeout(mut self, timeout: u32) -> Self {
+//!     self.timeout = timeout;
+//!     self
+//! }
+//!
+//! /// The connection timeout, in seconds.
+//! pub fn set_timeout(&mut self, timeout: u32) -> &mut Self {
+//!     self.timeout = timeout;
+//!     self
+//! }
+//! ```
+//!
+//! ## Struct Options
+//! *(`prefix`, `set`, `vis`)*
+//!
+//!  * **`prefix`** (default: `"with_"`)
+//!
+//!    The prefix of the chaining method names.
+//!
+//!  * **`set`** (default: unset)
+//!
+//!    Also generate `set_<field>(&mut self, value) -> &mut Self` methods for all fields.
+//!
+//!  * **`vis`** (default: `"pub"`)
+//!
+//!    The visibility of the generated methods.
+//!
+//! ## Field Options
+//! *(`skip`, `rename`, `into`, `strip_option`, `each`, `set`, `vis`)*
+//!
+//!  * **`skip`** (default: unset)
+//!
+//!    Don't generate methods for this field. Not allowed with other field options.
+//!
+//!  * **`rename`** (default: unset)
+//!
+//!    Use this name instead of the field name in method names.
+//!
+//!  * **`into`**, **`strip_option`** (default: unset)
+//!
+//!    Same as the `#[fluent]` parameter options.
+//!
+//!  * **`each`** (default: unset)
+//!
+//!    Also generate a method with this name adding a single item to a collection field
+//!    (e.g. a `Vec` or a `HashMap`) using `Extend`. Not allowed with *`into`* or *`strip_option`*.
+//!
+//!  * **`set`** (default: unset)
+//!
+//!    Same as the struct option, for this field only.
+//!
+//!  * **`vis`** (default: the struct's *`vis`*)
+//!
+//!    The visibility of the methods of this field.
+//!
 //!
 //! # Full Example
 //!
@@ -314,6 +1100,7 @@
 
 extern crate proc_macro;
 extern crate proc_macro2;
+extern crate toml;
 
 #[macro_use]
 extern crate syn;
@@ -323,15 +1110,19 @@ extern crate syn;
 extern crate quote;
 
 mod config;
+mod derive;
 mod impl_block;
+mod manifest;
 mod method;
+mod naming;
+mod params;
 mod type_utils;
 
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
-use syn::{Attribute, ImplItem, ItemImpl};
+use syn::{Attribute, DeriveInput, ImplItem, ItemImpl};
 
-use config::MacroConfig;
+use config::AttrInfo;
 
 // Dummy proc-macro for default overrides
 #[proc_macro_attribute]
@@ -347,8 +1138,16 @@ pub fn fluent_impl(args: TokenStream, input: TokenStream) -> TokenStream {
     let args: TokenStream2 = args.into();
     let attr: Attribute = parse_quote! { #[fluent_impl(#args)] };
     let attr_info = config::parse_config_from_attr(&attr).expect("Failed to parse macro attributes");
-    let macro_config = config::get_proc_macro_config(attr_info).expect("Failed to get config");
-    gen_fluent(input.into(), &macro_config)
+    gen_fluent(input.into(), attr_info)
+        .expect("Failed to generate fluent methods")
+        .into()
+}
+
+#[proc_macro_derive(Fluent, attributes(fluent))]
+/// Check the top-level documentation of this crate
+pub fn derive_fluent(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).expect("Failed to parse derive input");
+    derive::gen_fluent_from_derive(&input)
         .expect("Failed to generate fluent methods")
         .into()
 }
@@ -366,10 +1165,22 @@ fn check_if_impl_item_method(input: TokenStream) -> Result<(), String> {
     Ok(())
 }
 
-fn gen_fluent(input: TokenStream2, macro_config: &MacroConfig) -> Result<TokenStream2, String> {
-    if let Ok(impl_block) = syn::parse2::<ItemImpl>(input) {
-        impl_block::gen_fluent_from_impl_block(&impl_block, macro_config)
+fn gen_fluent(input: TokenStream2, attr_info: AttrInfo) -> Result<TokenStream2, String> {
+    let (input, mut param_configs) = params::take_param_configs(input)?;
+    if let Ok(impl_block) = syn::parse2::<ItemImpl>(input.clone()) {
+        let macro_config = config::get_proc_macro_config(attr_info)?;
+        let tokens = impl_block::gen_fluent_from_impl_block(&impl_block, macro_config, &param_configs)?;
+        let manifest_dep = manifest::manifest_dep();
+        Ok(quote! { #tokens #manifest_dep })
+    } else if let Ok(ImplItem::Method(method)) = syn::parse2::<ImplItem>(input) {
+        // Options passed to a method-level `#[fluent_impl]` are method options
+        let mut method_config = config::get_method_config(attr_info, None)?;
+        method_config.params = param_configs.remove(&method.sig.ident.to_string()).unwrap_or_default();
+        // An associated const here, since the expansion is in an impl block
+        let tokens = method::gen_fluent_from_method(&method, method_config)?;
+        let manifest_dep = manifest::manifest_dep();
+        Ok(quote! { #tokens #manifest_dep })
     } else {
-        Err("fluent_impl only applies to impl blocks")?
+        Err("fluent_impl only applies to impl blocks and methods in impl blocks")?
     }
 }
@@ -0,0 +1,164 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::PathBuf;
+
+use proc_macro2::TokenStream as TokenStream2;
+use toml::{self, Value};
+
+use config::AttrInfo;
+use naming;
+
+const METADATA_TABLE: &str = "[package.metadata.fluent-impl]";
+
+thread_local! {
+    // Parsed manifests (or the errors from parsing them) per CARGO_MANIFEST_DIR, so
+    // Cargo.toml is only read once per crate and not on every expansion.
+    static MANIFEST_CACHE: RefCell<HashMap<OsString, Result<ManifestConfig, String>>> = RefCell::new(HashMap::new());
+}
+
+// Block-level options set in the manifest of the crate using the macro.
+#[derive(Default, Clone)]
+pub(crate) struct ManifestConfig {
+    pub(crate) defaults: AttrInfo,
+    pub(crate) presets: Vec<(String, AttrInfo)>,
+}
+
+impl ManifestConfig {
+    pub(crate) fn take_preset(&mut self, preset: &str) -> Result<AttrInfo, String> {
+        match self.presets.iter().position(|(name, _)| name == preset) {
+            Some(pos) => Ok(self.presets.remove(pos).1),
+            None => Err(format!("unknown preset {}, presets are set in {}.presets", preset, METADATA_TABLE)),
+        }
+    }
+}
+
+// An item including the manifest, so the expansion is redone when it changes. Cargo
+// doesn't rebuild crates when only `package.metadata` changes otherwise.
+pub(crate) fn manifest_dep() -> Option<TokenStream2> {
+    let manifest_path = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR")?).join("Cargo.toml");
+    if !manifest_path.is_file() {
+        return None;
+    }
+    // `include_bytes!()` resolves relative paths from the source file
+    let manifest_path = env::current_dir().ok()?.join(manifest_path);
+    let manifest_path = manifest_path.to_str()?;
+    let ident = naming::unique_ident("__FLUENT_IMPL_MANIFEST");
+    Some(quote! {
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        const #ident: &'static [u8] = include_bytes!(#manifest_path);
+    })
+}
+
+pub(crate) fn get_manifest_config() -> Result<ManifestConfig, String> {
+    // Not built by cargo, nothing to read
+    let manifest_dir = match env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => dir,
+        None => return Ok(ManifestConfig::default()),
+    };
+
+    MANIFEST_CACHE.with(|cache| {
+        cache
+            .borrow_mut()
+            .entry(manifest_dir.clone())
+            .or_insert_with(|| read_manifest_config(PathBuf::from(manifest_dir).join("Cargo.toml")))
+            .clone()
+    })
+}
+
+fn read_manifest_config(manifest_path: PathBuf) -> Result<ManifestConfig, String> {
+    let manifest = match fs::read_to_string(&manifest_path) {
+        Ok(manifest) => manifest,
+        Err(_) => return Ok(ManifestConfig::default()),
+    };
+
+    let manifest = manifest
+        .parse::<Value>()
+        .map_err(|e| format!("failed to parse {}: {}", manifest_path.display(), e))?;
+
+    let metadata = manifest
+        .get("package")
+        .and_then(|p| p.get("metadata"))
+        .and_then(|m| m.get("fluent-impl"));
+
+    match metadata {
+        Some(metadata) => parse_metadata(metadata).map_err(|e| format!("{}: {}", manifest_path.display(), e)),
+        None => Ok(ManifestConfig::default()),
+    }
+}
+
+fn parse_metadata(metadata: &Value) -> Result<ManifestConfig, String> {
+    let mut manifest_config = ManifestConfig::default();
+    let metadata = metadata
+        .as_table()
+        .ok_or_else(|| format!("expected {} to be a table", METADATA_TABLE))?;
+
+    for (key, value) in metadata {
+        if key == "presets" {
+            let presets = value
+                .as_table()
+                .ok_or_else(|| format!("expected {}.presets to be a table", METADATA_TABLE))?;
+            for (preset, value) in presets {
+                let table_name = format!("{}.presets.{}", METADATA_TABLE, preset);
+                let preset_table = value
+                    .as_table()
+                    .ok_or_else(|| format!("expected {} to be a table", table_name))?;
+                let attr_info = attr_info_from_table(preset_table, &table_name)?;
+                manifest_config.presets.push((preset.clone(), attr_info));
+            }
+        }
+    }
+
+    let defaults: toml::value::Table = metadata
+        .iter()
+        .filter(|(key, _)| *key != "presets")
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    manifest_config.defaults = attr_info_from_table(&defaults, METADATA_TABLE)?;
+
+    Ok(manifest_config)
+}
+
+// `true` booleans are words, strings are name=value pairs, and tables of strings are lists
+// of name=value pairs. `false` turns a word off, overriding earlier layers.
+fn attr_info_from_table(table: &toml::value::Table, table_name: &str) -> Result<AttrInfo, String> {
+    let mut attr_info = AttrInfo::default();
+
+    for (key, value) in table {
+        match value {
+            Value::Boolean(true) => attr_info.names.push(key.clone()),
+            Value::Boolean(false) => attr_info.off_names.push(key.clone()),
+            Value::String(s) => attr_info.name_vals.push((key.clone(), s.clone())),
+            Value::Table(t) => {
+                let mut name_vals = Vec::with_capacity(t.len());
+                for (name, value) in t {
+                    match value {
+                        Value::String(s) => name_vals.push((name.clone(), s.clone())),
+                        _ => Err(format!("expected a string value for {} in {}.{}, found: {}", name, table_name, key, value))?,
+                    }
+                }
+                attr_info.lists.push((key.clone(), name_vals));
+            },
+            _ => Err(format!(
+                "expected a string, a boolean or a table value for {} in {}, found: {}",
+                key, table_name, value
+            ))?,
+        }
+    }
+
+    Ok(attr_info)
+}
@@ -9,26 +9,50 @@
     file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
 */
 
-use proc_macro2::Span;
+use proc_macro2::{Group, Span, TokenStream as TokenStream2, TokenTree};
 use syn::{
-    punctuated::Punctuated, token::Comma, Attribute, Expr, FnArg, GenericParam, Generics, Ident, ImplItemMethod, Pat,
-    ReturnType, Type, Visibility,
+    self,
+    punctuated::Punctuated, token::Comma, ArgCaptured, Attribute, Expr, FnArg, GenericParam, Generics, Ident, ImplItemMethod,
+    Lifetime, Lit, Meta, MetaNameValue, NestedMeta, Pat, ReturnType, TraitItemMethod, Type, Visibility,
 };
 
-use config::{self, MacroConfig, MethodConfig};
+use config::{self, AttrInfo, MacroConfig, MethodConfig, ParamConfig};
+use naming;
 use type_utils as t;
 
-pub(crate) fn get_method_config(attrs: &[Attribute]) -> Result<MethodConfig, String> {
-    let mut method_config = MethodConfig::default();
+// Options targeting a profile other than `profile` are ignored.
+pub(crate) fn get_method_config(
+    attrs: &[Attribute],
+    profile: Option<&str>,
+    pre_config: Option<MethodConfig>,
+) -> Result<MethodConfig, String> {
+    let mut method_config = pre_config.unwrap_or_default();
 
     for opts in attrs.iter().filter(|a| a.path == parse_quote! { fluent_impl_opts }) {
-        let attr_info = config::parse_config_from_attr(&opts)?;
+        let mut attr_info = config::parse_config_from_attr(opts)?;
+        if let Some(opts_profile) = config::take_name_val(&mut attr_info, "profile") {
+            if Some(&*opts_profile) != profile {
+                continue;
+            }
+        }
         method_config = config::get_method_config(attr_info, Some(method_config))?;
     }
 
     Ok(method_config)
 }
 
+// Profiles targeted by method-level options.
+pub(crate) fn get_method_profiles(attrs: &[Attribute]) -> Result<Vec<String>, String> {
+    let mut profiles = Vec::new();
+    for opts in attrs.iter().filter(|a| a.path == parse_quote! { fluent_impl_opts }) {
+        let mut attr_info = config::parse_config_from_attr(opts)?;
+        if let Some(profile) = config::take_name_val(&mut attr_info, "profile") {
+            profiles.push(profile);
+        }
+    }
+    Ok(profiles)
+}
+
 pub(crate) fn try_fluentable(
     method: &ImplItemMethod,
     macro_config: &MacroConfig,
@@ -37,7 +61,9 @@ pub(crate) fn try_fluentable(
     let err_msg = "fluent_impl only applies to `&mut self` methods and no return value";
 
     // Check if method returns anything
-    if method.sig.decl.output != ReturnType::Default {
+    if method_config.nested || is_setter(method, macro_config, method_config) {
+        check_accessor(method, method_config)?;
+    } else if method.sig.decl.output != ReturnType::Default {
         Err(err_msg)?
     }
 
@@ -57,7 +83,8 @@ pub(crate) fn try_fluentable(
 
     match method.vis {
         Visibility::Public(_) => (),
-        _ => if !macro_config.non_public && !method_config.non_public {
+        // An explicit method-level visibility enables non-public methods too
+        _ => if !macro_config.non_public && !method_config.non_public && method_config.vis.is_none() {
             Err("generating a chaining method from this non-public method was not enabled")?;
         },
     }
@@ -69,37 +96,796 @@ pub(crate) fn try_fluentable(
     Ok(())
 }
 
+pub(crate) fn gen_fluent_from_method(method: &ImplItemMethod, method_config: MethodConfig) -> Result<TokenStream2, String> {
+    // There is no impl block to take options from, so block-level defaults are used.
+    let mut macro_config = config::get_proc_macro_config(AttrInfo::default())?;
+    // The chaining method is generated right below the chain-able one
+    macro_config.inblock = true;
+    macro_config.builder = None;
+    let method_config = get_method_config(&method.attrs, None, Some(method_config))?;
+    try_fluentable(method, &macro_config, &method_config)?;
+    if method_config.group.is_some() {
+        Err("group only applies to methods in impl blocks")?;
+    }
+    if method_config.extend || method_config.from_iter || method_config.op.is_some() {
+        Err("extend, from_iter and op only apply to methods in impl blocks")?;
+    }
+    if method_config.required {
+        Err("required only applies to methods in builder profiles")?;
+    }
+
+    let ty = parse_quote! { Self };
+    let fluent_method = fluent_from_fluentable(method.clone(), &macro_config, &method_config, &ty)?;
+    let aliases = fluent_aliases(method, &fluent_method, &macro_config, &method_config)?;
+    let variants = fluent_variants(&fluent_method, &macro_config, &method_config)?;
+    let presets = fluent_presets(method, &fluent_method, &macro_config, &method_config)?;
+    let nested_chain = fluent_nested_chain(method, &fluent_method, &macro_config, &method_config)?;
+
+    let mut method = method.clone();
+    if method_config.see_also {
+        method.attrs.extend(fluent_see_also(&fluent_method, "Self"));
+    }
+    Ok(quote! { #method #fluent_method #nested_chain #(#aliases)* #(#variants)* #(#presets)* })
+}
+
 pub(crate) fn fluent_from_fluentable(
     method: ImplItemMethod,
     macro_config: &MacroConfig,
+    method_config: &MethodConfig,
     ty: &Type,
 ) -> Result<ImplItemMethod, String> {
     let mut fluent_method = method;
     let b_ident = fluent_method.sig.ident.clone();
-    let doc = fluent_doc(&fluent_method, macro_config)?;
-    let doc = doc.replace("%f%", &fluent_method.sig.ident.to_string());
-    let doc = doc.replace("%t%", &t::bare_ty_str(ty)?);
+    let f_ident = fluent_ident(&fluent_method, macro_config, method_config)?;
+    let params = method_params(&fluent_method, method_config)?;
+    let child_ty = accessor_child_ty(&fluent_method).cloned();
+    let setter = is_setter(&fluent_method, macro_config, method_config);
+    let doc = fluent_doc(macro_config, method_config);
+    let doc = doc.replace("%f%", &b_ident.to_string());
+    let doc = doc.replace("%t%", &t::ty_str(ty, macro_config.type_generics));
+    let doc = doc.replace("%n%", &f_ident.to_string());
+    let doc = doc.replace("%a%", &args_str(&fluent_method.sig.decl.inputs));
+    let doc = doc.replace("%r%", &receiver_str(&fluent_method.sig.decl.inputs));
+    // Last, so placeholders in the original doc are left alone
+    let doc = doc.replace("%d%", &original_doc(&fluent_method.attrs));
+    let doc = doc.trim_start().to_string()
+        + &fluent_doc_example(&fluent_method, &f_ident, &params, macro_config, method_config, ty)
+        + &fluent_doc_links(&b_ident, macro_config, ty);
+
+    fluent_method.sig.ident = f_ident;
+    // Replace original doc with ours, and only keep forwarded attributes
+    let attrs = fluent_attrs(&fluent_method.attrs, macro_config, method_config)?;
+    fluent_method.attrs = vec![parse_quote! { #[doc = #doc] }];
+    fluent_method.attrs.extend(attrs);
 
-    fluent_method.sig.ident = Ident::new(&fluent_ident(&fluent_method, macro_config)?, Span::call_site());
-    // Remove original doc and add ours
-    fluent_method.attrs.retain(|a| a.path != parse_quote!{ doc });
-    fluent_method.attrs.push(parse_quote! { #[doc = #doc] });
+    // The block-level cfg is also on the generated block, docs only need to show it once
+    let block_doc_cfg = macro_config.doc_cfg
+        && macro_config.trait_.is_none()
+        && macro_config.builder.is_none()
+        && (macro_config.inblock || method_config.inblock);
+    if let Some(cfg) = &macro_config.cfg {
+        fluent_method.attrs.extend(cfg_attrs(cfg, block_doc_cfg)?);
+    }
+    if let Some(cfg) = &method_config.cfg {
+        fluent_method.attrs.extend(cfg_attrs(cfg, macro_config.doc_cfg || method_config.doc_cfg)?);
+    }
+
+    if macro_config.doc_alias || method_config.doc_alias {
+        let alias = naming::ident_name(&b_ident);
+        fluent_method.attrs.push(parse_quote! { #[doc(alias = #alias)] });
+    }
+
+    if macro_config.hidden || method_config.hidden {
+        fluent_method.attrs.push(parse_quote! { #[doc(hidden)] });
+    }
+
+    if let Some(vis) = method_config.vis.as_ref().or(macro_config.vis.as_ref()) {
+        fluent_method.vis = syn::parse_str(vis).map_err(|_| format!("invalid vis attribute: {:?}", vis))?;
+    }
 
     // Always Some
-    match fluent_method.sig.decl.inputs.iter_mut().nth(0) {
+    match fluent_method.sig.decl.inputs.iter_mut().next() {
         Some(first_arg) => *first_arg = parse_quote! { mut self },
         None => unreachable!(),
     };
 
+    let generic_params = get_generic_params(&fluent_method.sig.decl.generics);
+    let receiver = receiver(macro_config);
+    match child_ty {
+        Some(child_ty) if method_config.nested => {
+            fluent_method.sig.decl.output = parse_quote! { -> Self };
+            fluent_method.sig.decl.inputs = parse_quote! { mut self, f: impl FnOnce(&mut #child_ty) };
+            fluent_method.block = parse_quote! { { f(#receiver.#b_ident::<#generic_params>()); self } };
+            return Ok(fluent_method);
+        },
+        Some(child_ty) if setter => {
+            fluent_method.sig.decl.output = parse_quote! { -> Self };
+            let value = if method_config.setter_into {
+                fluent_method.sig.decl.inputs = parse_quote! { mut self, value: impl Into<#child_ty> };
+                quote! { value.into() }
+            } else {
+                fluent_method.sig.decl.inputs = parse_quote! { mut self, value: #child_ty };
+                quote! { value }
+            };
+            fluent_method.block = parse_quote! { { *#receiver.#b_ident::<#generic_params>() = #value; self } };
+            return Ok(fluent_method);
+        },
+        _ => (),
+    }
+
     fluent_method.sig.decl.output = parse_quote! { -> Self };
     simplify_fn_args(&mut fluent_method.sig.decl.inputs);
-    let call_args = get_call_args(&fluent_method.sig.decl.inputs);
-    let generic_params = get_generic_params(&fluent_method.sig.decl.generics);
-    fluent_method.block = parse_quote! { { self.#b_ident::<#generic_params>(#call_args); self } };
+    let call_args = apply_param_configs(&mut fluent_method.sig.decl.inputs, &params)?;
+    fluent_method.block = parse_quote! { { #receiver.#b_ident::<#generic_params>(#call_args); self } };
 
     Ok(fluent_method)
 }
 
+// What chain-able methods are called on. Builder methods call them on the wrapped value.
+fn receiver(macro_config: &MacroConfig) -> TokenStream2 {
+    match macro_config.builder {
+        Some(_) => quote! { self.0 },
+        None => quote! { self },
+    }
+}
+
+// `#[cfg(...)]`, and optionally the attribute showing it in docs.
+pub(crate) fn cfg_attrs(cfg: &str, doc_cfg: bool) -> Result<Vec<Attribute>, String> {
+    let cfg = config::parse_cfg(cfg)?;
+    let mut attrs = vec![parse_quote! { #[cfg(#cfg)] }];
+    if doc_cfg {
+        attrs.push(parse_quote! { #[cfg_attr(docsrs, doc(cfg(#cfg)))] });
+    }
+    Ok(attrs)
+}
+
+// Attributes of the chain-able method forwarded to the chaining method by default.
+// `#[doc(cfg(...))]` is forwarded too.
+const FORWARDED_ATTRS: &[&str] = &["cfg", "deprecated", "inline", "track_caller"];
+
+// Forwarded attributes of the chain-able method, and the attributes added to every chaining method.
+fn fluent_attrs(attrs: &[Attribute], macro_config: &MacroConfig, method_config: &MethodConfig) -> Result<Vec<Attribute>, String> {
+    let attr_paths = |lists: [&Option<String>; 2]| -> Result<Vec<String>, String> {
+        let mut paths = Vec::new();
+        for list in lists.iter().filter_map(|list| list.as_ref()) {
+            for path in config::parse_attr_paths(list)? {
+                paths.push(t::tokens_str(quote! { #path }));
+            }
+        }
+        Ok(paths)
+    };
+    let path_str = |attr: &Attribute| {
+        let path = &attr.path;
+        t::tokens_str(quote! { #path })
+    };
+
+    let mut forward = attr_paths([&macro_config.forward_attrs, &method_config.forward_attrs])?;
+    forward.extend(FORWARDED_ATTRS.iter().map(|path| path.to_string()));
+    let drop = attr_paths([&macro_config.drop_attrs, &method_config.drop_attrs])?;
+
+    let mut ret: Vec<Attribute> = attrs
+        .iter()
+        .filter(|attr| {
+            let path = path_str(attr);
+            !drop.contains(&path) && (forward.contains(&path) || (path == "doc" && is_doc_cfg(attr)))
+        })
+        .cloned()
+        .collect();
+
+    // The chaining method returns the value it modified
+    if !drop.iter().any(|path| path == "must_use") && !ret.iter().any(|attr| path_str(attr) == "must_use") {
+        ret.push(parse_quote! { #[must_use] });
+    }
+
+    // The chaining method calls the chain-able one, even if it's deprecated
+    if attrs.iter().any(|attr| path_str(attr) == "deprecated") {
+        ret.push(parse_quote! { #[allow(deprecated)] });
+    }
+
+    for add_attrs in [&macro_config.add_attrs, &method_config.add_attrs].iter().filter_map(|a| a.as_ref()) {
+        ret.extend(config::parse_attrs(add_attrs)?);
+    }
+
+    Ok(ret)
+}
+
+fn is_doc_cfg(attr: &Attribute) -> bool {
+    match attr.interpret_meta() {
+        Some(Meta::List(list)) => list.nested.iter().any(|nested| match nested {
+            NestedMeta::Meta(meta) => meta.name() == "cfg",
+            _ => false,
+        }),
+        _ => false,
+    }
+}
+
+// A chaining method with an explicit visibility may be more visible than the chain-able
+// one. Leaking a private type through an associated type of a trait impl is a hard error,
+// unlike in method signatures where it's only a lint, so argument types are checked that way.
+// Argument types that depend on generic parameters are not checked.
+pub(crate) fn fluent_vis_check(fluent_method: &ImplItemMethod, ty: &Type, generics: &Generics) -> Option<TokenStream2> {
+    let generic_names: Vec<String> = generics
+        .params
+        .iter()
+        .chain(&fluent_method.sig.decl.generics.params)
+        .map(|param| match param {
+            GenericParam::Lifetime(l) => l.lifetime.ident.to_string(),
+            GenericParam::Const(p) => p.ident.to_string(),
+            GenericParam::Type(t) => t.ident.to_string(),
+        })
+        .collect();
+
+    let self_ty = quote! { #ty };
+    let self_ty = if self_ty.to_string() == "Self" {
+        None
+    } else {
+        checkable_ty(self_ty, &generic_names, None)
+    };
+
+    let arg_tys: Vec<TokenStream2> = fluent_method
+        .sig
+        .decl
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Captured(cap) => Some(&cap.ty),
+            FnArg::Ignored(ty) => Some(ty),
+            _ => None,
+        })
+        .filter_map(|arg_ty| checkable_ty(quote! { #arg_ty }, &generic_names, self_ty.as_ref()))
+        .collect();
+
+    if arg_tys.is_empty() {
+        return None;
+    }
+
+    let vis = &fluent_method.vis;
+    let cfgs = fluent_method.attrs.iter().filter(|a| a.path == parse_quote! { cfg });
+    // A named const, since `const _` needs Rust 1.37
+    let check_ident = naming::unique_ident("__FLUENT_IMPL_VIS_CHECK");
+    Some(quote! {
+        #(#cfgs)*
+        #[allow(dead_code)]
+        const #check_ident: () = {
+            #[allow(dead_code)]
+            #vis trait FluentImplVisCheck { type Args; }
+            impl FluentImplVisCheck for () { type Args = fn(#(#arg_tys),*); }
+        };
+    })
+}
+
+// Replace `Self` with the self type. None if the type can't be named outside the impl block.
+fn checkable_ty(tokens: TokenStream2, generic_names: &[String], self_ty: Option<&TokenStream2>) -> Option<TokenStream2> {
+    let mut ret = TokenStream2::new();
+    for tt in tokens {
+        match tt {
+            TokenTree::Ident(ref ident) if ident == "impl" || generic_names.contains(&ident.to_string()) => return None,
+            TokenTree::Ident(ref ident) if ident == "Self" => ret.extend(self_ty?.clone()),
+            TokenTree::Group(group) => {
+                let stream = checkable_ty(group.stream(), generic_names, self_ty)?;
+                ret.extend(Some(TokenTree::Group(Group::new(group.delimiter(), stream))));
+            },
+            tt => ret.extend(Some(tt)),
+        }
+    }
+    Some(ret)
+}
+
+// Deprecated chaining methods with old names, forwarding to the chaining method.
+pub(crate) fn fluent_aliases(
+    method: &ImplItemMethod,
+    fluent_method: &ImplItemMethod,
+    macro_config: &MacroConfig,
+    method_config: &MethodConfig,
+) -> Result<Vec<ImplItemMethod>, String> {
+    let mut alias_names: Vec<String> = Vec::new();
+    if let Some(deprecated_alias) = &method_config.deprecated_alias {
+        alias_names.extend(deprecated_alias.split(',').map(|a| a.trim().into()));
+    }
+
+    // Only applies if the chaining method name depends on the block-level prefix
+    if let Some(deprecated_prefix) = &macro_config.deprecated_prefix {
+        let uses_block_prefix = macro_config.name_template.is_none()
+            && method_config.name.is_none()
+            && method_config.name_template.is_none()
+            && method_config.prefix.is_none();
+        if uses_block_prefix {
+            let b_ident = fluent_base_name(method, macro_config, method_config);
+            let suffix = method_config.suffix.as_ref().unwrap_or(&macro_config.suffix);
+            alias_names.push(format!("{}{}{}", deprecated_prefix, b_ident, suffix));
+        }
+    }
+
+    let f_ident = &fluent_method.sig.ident;
+    let f_ident_str = f_ident.to_string();
+    let note = format!("use `{}` instead", f_ident_str);
+    // Both the alias and the chaining method are in the same impl block or trait
+    let doc = format!("Deprecated alias of [`{0}()`].\n\n [`{0}()`]: Self::{0}", f_ident_str);
+
+    let mut aliases = Vec::with_capacity(alias_names.len());
+    for alias_name in alias_names {
+        let mut alias = fluent_method.clone();
+        alias.sig.ident = naming::make_ident(&alias_name)?;
+        alias.attrs.retain(|a| a.path != parse_quote! { doc } && a.path != parse_quote! { deprecated });
+        alias.attrs.push(parse_quote! { #[doc = #doc] });
+        alias.attrs.push(parse_quote! { #[deprecated(note = #note)] });
+
+        // Always Some
+        match alias.sig.decl.inputs.iter_mut().next() {
+            Some(first_arg) => *first_arg = parse_quote! { self },
+            None => unreachable!(),
+        };
+
+        let call_args = get_call_args(&alias.sig.decl.inputs);
+        let generic_params = get_generic_params(&alias.sig.decl.generics);
+        alias.block = parse_quote! { { self.#f_ident::<#generic_params>(#call_args) } };
+        aliases.push(alias);
+    }
+
+    Ok(aliases)
+}
+
+// Variants of a chaining method, forwarding to it: `<name>_if` taking a condition,
+// `<name>_opt` taking the arguments in an `Option`, and each taking an iterator of them.
+pub(crate) fn fluent_variants(
+    fluent_method: &ImplItemMethod,
+    macro_config: &MacroConfig,
+    method_config: &MethodConfig,
+) -> Result<Vec<ImplItemMethod>, String> {
+    let f_ident = &fluent_method.sig.ident;
+    let f_name = naming::ident_name(f_ident);
+    let generic_params = get_generic_params(&fluent_method.sig.decl.generics);
+    let mut variant_base = fluent_method.clone();
+    variant_base.attrs.retain(|a| a.path != parse_quote! { doc });
+    // Always Some
+    match variant_base.sig.decl.inputs.iter_mut().next() {
+        Some(first_arg) => *first_arg = parse_quote! { self },
+        None => unreachable!(),
+    };
+
+    let call_args = get_call_args(&variant_base.sig.decl.inputs);
+    let unused_name = |name: &str| unused_ident(name, &call_args);
+
+    let mut variants = Vec::with_capacity(3);
+    if macro_config.if_variant || method_config.if_variant {
+        let mut variant = variant_base.clone();
+        variant.sig.ident = naming::make_ident(&format!("{}_if", f_name))?;
+        let cond = unused_name("cond");
+        let doc = format!(
+            "Call [`{0}()`] only if `{1}` is `true`.\n\n [`{0}()`]: Self::{0}",
+            f_ident, cond
+        );
+        variant.attrs.insert(0, parse_quote! { #[doc = #doc] });
+        variant.sig.decl.inputs.insert(1, parse_quote! { #cond: bool });
+        variant.block = parse_quote! {{
+            if #cond { self.#f_ident::<#generic_params>(#call_args) } else { self }
+        }};
+        variants.push(variant);
+    }
+
+    // There is nothing to wrap in an Option without arguments
+    if (macro_config.opt_variant || method_config.opt_variant) && !call_args.is_empty() {
+        let mut variant = variant_base.clone();
+        variant.sig.ident = naming::make_ident(&format!("{}_opt", f_name))?;
+        let args: Vec<FnArg> = variant.sig.decl.inputs.iter().skip(1).cloned().collect();
+        let arg_tys: Vec<&Type> = args
+            .iter()
+            .filter_map(|arg| match arg {
+                FnArg::Captured(cap) => Some(&cap.ty),
+                _ => None,
+            })
+            .collect();
+        let (opt_arg, opt_ty, pat) = if call_args.len() == 1 {
+            let arg = &call_args[0];
+            let ty = arg_tys[0];
+            (quote! { #arg }, quote! { #ty }, quote! { #arg })
+        } else {
+            let arg = unused_name("args");
+            (quote! { #arg }, quote! { (#(#arg_tys),*) }, quote! { (#call_args) })
+        };
+        let doc = format!(
+            "Call [`{0}()`] only if `{1}` is `Some`.\n\n [`{0}()`]: Self::{0}",
+            f_ident, opt_arg
+        );
+        variant.attrs.insert(0, parse_quote! { #[doc = #doc] });
+        variant.sig.decl.inputs = parse_quote! { self, #opt_arg: Option<#opt_ty> };
+        variant.block = parse_quote! {{
+            match #opt_arg {
+                Some(#pat) => self.#f_ident::<#generic_params>(#call_args),
+                None => self,
+            }
+        }};
+        variants.push(variant);
+    }
+
+    if (macro_config.each.is_some() || method_config.each) && !call_args.is_empty() {
+        let mut variant = variant_base.clone();
+        variant.sig.ident = match (&method_config.each_name, &macro_config.each) {
+            (Some(each_name), _) => naming::make_ident(each_name)?,
+            (None, Some(each)) => naming::make_ident(&each.replace("{}", &f_name))?,
+            (None, None) => naming::make_ident(&format!("{}s", f_name))?,
+        };
+        let arg_tys = variant.sig.decl.inputs.iter().skip(1).filter_map(|arg| match arg {
+            FnArg::Captured(cap) => Some(&cap.ty),
+            _ => None,
+        });
+        // Items of multiple arguments are tuples, unpacked in the call
+        let (items, item_ty, pat) = if call_args.len() == 1 {
+            let arg = &call_args[0];
+            (unused_name(&format!("{}s", quote!(#arg))), quote! { #(#arg_tys)* }, quote! { #arg })
+        } else {
+            (unused_name("items"), quote! { (#(#arg_tys),*) }, quote! { (#call_args) })
+        };
+        let doc = format!(
+            "Call [`{0}()`] with every item of `{1}`.\n\n [`{0}()`]: Self::{0}",
+            f_ident, items
+        );
+        variant.attrs.insert(0, parse_quote! { #[doc = #doc] });
+        variant.sig.decl.inputs = parse_quote! { mut self, #items: impl IntoIterator<Item = #item_ty> };
+        variant.block = parse_quote! {{
+            for #pat in #items {
+                self = self.#f_ident::<#generic_params>(#call_args);
+            }
+            self
+        }};
+        variants.push(variant);
+    }
+
+    Ok(variants)
+}
+
+// Accessors with a chaining method taking a closure (nested) or a value (setter).
+pub(crate) fn check_accessor(method: &ImplItemMethod, method_config: &MethodConfig) -> Result<(), String> {
+    match accessor_child_ty(method) {
+        Some(_) => Ok(()),
+        None => Err(format!(
+            "{} only applies to `&mut self` methods with no arguments, returning `&mut T`: {}",
+            if method_config.nested { "nested" } else { "setter" },
+            method.sig.ident
+        )),
+    }
+}
+
+// Setters are also generated for all `*_mut` accessors if `setters` is set, unless they are nested.
+fn is_setter(method: &ImplItemMethod, macro_config: &MacroConfig, method_config: &MethodConfig) -> bool {
+    let auto_setter = macro_config.setters
+        && !method_config.nested
+        && naming::ident_name(&method.sig.ident).ends_with("_mut")
+        && accessor_child_ty(method).is_some();
+    method_config.setter || auto_setter
+}
+
+// The child type of an accessor, `T` in `fn child_mut(&mut self) -> &mut T`.
+fn accessor_child_ty(method: &ImplItemMethod) -> Option<&Type> {
+    match method.sig.decl.inputs.iter().collect::<Vec<_>>()[..] {
+        [FnArg::SelfRef(arg)] if arg.mutability.is_some() => (),
+        _ => return None,
+    }
+    match &method.sig.decl.output {
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Reference(reference) if reference.mutability.is_some() => Some(&reference.elem),
+            _ => None,
+        },
+        ReturnType::Default => None,
+    }
+}
+
+// The `<name>_chain` method of a nested accessor, passing the child to a closure by value,
+// and putting back the returned child.
+pub(crate) fn fluent_nested_chain(
+    method: &ImplItemMethod,
+    fluent_method: &ImplItemMethod,
+    macro_config: &MacroConfig,
+    method_config: &MethodConfig,
+) -> Result<Option<ImplItemMethod>, String> {
+    let child_ty = match accessor_child_ty(method) {
+        Some(child_ty) if method_config.nested_chain => child_ty,
+        _ => return Ok(None),
+    };
+
+    let b_ident = &method.sig.ident;
+    let f_ident = &fluent_method.sig.ident;
+    let mut chain = fluent_method.clone();
+    chain.sig.ident = naming::make_ident(&format!("{}_chain", naming::ident_name(f_ident)))?;
+    chain.attrs.retain(|a| a.path != parse_quote! { doc });
+    let doc = format!(
+        "Like [`{0}()`], with a closure taking the child by value, and returning it. The child is \
+         left with its default value while the closure runs.\n\n [`{0}()`]: Self::{0}",
+        f_ident
+    );
+    chain.attrs.insert(0, parse_quote! { #[doc = #doc] });
+    chain.sig.decl.inputs = parse_quote! { mut self, f: impl FnOnce(#child_ty) -> #child_ty };
+    let generic_params = get_generic_params(&chain.sig.decl.generics);
+    let receiver = receiver(macro_config);
+    chain.block = parse_quote! {{
+        let child = ::std::mem::replace(#receiver.#b_ident::<#generic_params>(), ::std::default::Default::default());
+        *#receiver.#b_ident::<#generic_params>() = f(child);
+        self
+    }};
+    Ok(Some(chain))
+}
+
+// Chaining methods passing fixed arguments, forwarding to the chaining method: the off
+// method of a flag, and named presets.
+pub(crate) fn fluent_presets(
+    method: &ImplItemMethod,
+    fluent_method: &ImplItemMethod,
+    macro_config: &MacroConfig,
+    method_config: &MethodConfig,
+) -> Result<Vec<ImplItemMethod>, String> {
+    let b_ident = &method.sig.ident;
+    let f_ident = &fluent_method.sig.ident;
+    let mut presets = Vec::with_capacity(method_config.presets.len() + 1);
+
+    if method_config.flag {
+        let off_name = match &method_config.flag_off {
+            Some(off_name) => off_name.clone(),
+            None => match naming::strip_prefix(&naming::ident_name(f_ident), "with_") {
+                Some(name) => format!("without_{}", name),
+                None => Err(format!("the off method name of flag {} can't be derived, set flag(off = \"...\")", f_ident))?,
+            },
+        };
+        let mut off = fluent_method.clone();
+        off.sig.ident = naming::make_ident(&off_name)?;
+        off.attrs.retain(|a| a.path != parse_quote! { doc });
+        let doc = format!("The opposite of [`{0}()`], passing `false` instead.\n\n [`{0}()`]: Self::{0}", f_ident);
+        off.attrs.insert(0, parse_quote! { #[doc = #doc] });
+        let generic_params = get_generic_params(&off.sig.decl.generics);
+        let receiver = receiver(macro_config);
+        off.block = parse_quote! { { #receiver.#b_ident::<#generic_params>(false); self } };
+        presets.push(off);
+    }
+
+    let f_args_len = fluent_method.sig.decl.inputs.len() - 1;
+    for (name, args) in &method_config.presets {
+        let args = config::parse_args(args)?;
+        if args.len() != f_args_len {
+            Err(format!("preset {} passes {} argument(s), {} takes {}", name, args.len(), f_ident, f_args_len))?;
+        }
+        let mut preset = fluent_method.clone();
+        preset.sig.ident = naming::make_ident(name)?;
+        // Generic arguments are inferred from the preset arguments
+        preset.sig.decl.generics = Generics::default();
+        preset.sig.decl.inputs = parse_quote! { self };
+        preset.attrs.retain(|a| a.path != parse_quote! { doc });
+        let doc = format!(
+            "Shorthand for [`{0}()`] with `{1}`.\n\n [`{0}()`]: Self::{0}",
+            f_ident,
+            t::tokens_str(quote! { #args })
+        );
+        preset.attrs.insert(0, parse_quote! { #[doc = #doc] });
+        preset.block = parse_quote! { { self.#f_ident(#args) } };
+        presets.push(preset);
+    }
+
+    Ok(presets)
+}
+
+// `Extend` and `FromIterator` impls calling a chain-able method with every item. Generic
+// parameters of the method become generic parameters of the impls.
+// `#[cfg]` of the chain-able method, and the block-level and method-level cfg options.
+fn std_impl_cfgs(
+    method: &ImplItemMethod,
+    macro_config: &MacroConfig,
+    method_config: &MethodConfig,
+) -> Result<Vec<Attribute>, String> {
+    let mut cfgs: Vec<Attribute> = method.attrs.iter().filter(|a| a.path == parse_quote! { cfg }).cloned().collect();
+    if let Some(cfg) = &macro_config.cfg {
+        cfgs.extend(cfg_attrs(cfg, macro_config.doc_cfg)?);
+    }
+    if let Some(cfg) = &method_config.cfg {
+        cfgs.extend(cfg_attrs(cfg, macro_config.doc_cfg || method_config.doc_cfg)?);
+    }
+    Ok(cfgs)
+}
+
+pub(crate) fn collect_impls(
+    method: &ImplItemMethod,
+    self_ty: &Type,
+    generics: &Generics,
+    macro_config: &MacroConfig,
+    method_config: &MethodConfig,
+) -> Result<TokenStream2, String> {
+    if !method_config.extend && !method_config.from_iter {
+        return Ok(TokenStream2::new());
+    }
+
+    let b_ident = &method.sig.ident;
+    let ImplArgs { arg_tys, lifetimes, call_args } = impl_args(method, "Extend and FromIterator impls")?;
+    let (item_ty, pat) = match arg_tys.len() {
+        1 => {
+            let arg = &call_args[0];
+            (quote! { #(#arg_tys)* }, quote! { #arg })
+        },
+        _ => (quote! { (#(#arg_tys),*) }, quote! { (#call_args) }),
+    };
+    let generics = impl_generics(generics, method, &lifetimes);
+
+    let cfgs = &std_impl_cfgs(method, macro_config, method_config)?;
+    let iter = unused_ident("iter", &call_args);
+    let mut impls = TokenStream2::new();
+
+    if method_config.extend {
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        impls.extend(quote! {
+            #(#cfgs)*
+            impl #impl_generics ::std::iter::Extend<#item_ty> for #self_ty #where_clause {
+                fn extend<FluentImplIter: ::std::iter::IntoIterator<Item = #item_ty>>(&mut self, #iter: FluentImplIter) {
+                    for #pat in #iter {
+                        self.#b_ident(#call_args);
+                    }
+                }
+            }
+        });
+    }
+
+    if method_config.from_iter {
+        let mut generics = generics.clone();
+        generics.make_where_clause().predicates.push(parse_quote! { #self_ty: ::std::default::Default });
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let collection = unused_ident("collection", &call_args);
+        impls.extend(quote! {
+            #(#cfgs)*
+            impl #impl_generics ::std::iter::FromIterator<#item_ty> for #self_ty #where_clause {
+                fn from_iter<FluentImplIter: ::std::iter::IntoIterator<Item = #item_ty>>(#iter: FluentImplIter) -> Self {
+                    let mut #collection: Self = ::std::default::Default::default();
+                    for #pat in #iter {
+                        #collection.#b_ident(#call_args);
+                    }
+                    #collection
+                }
+            }
+        });
+    }
+
+    Ok(impls)
+}
+
+// Operator trait impls calling a chain-able method: the `*Assign` trait passed to `op`, and
+// its by-value counterpart.
+pub(crate) fn op_impls(
+    method: &ImplItemMethod,
+    self_ty: &Type,
+    generics: &Generics,
+    macro_config: &MacroConfig,
+    method_config: &MethodConfig,
+) -> Result<TokenStream2, String> {
+    let (op_trait, op_fn) = match &method_config.op {
+        // Checked in config
+        Some(op) => match config::ASSIGN_OPS.iter().find(|(op_trait, _)| op_trait == op) {
+            Some(&(op_trait, op_fn)) => (op_trait, op_fn),
+            None => unreachable!(),
+        },
+        None => return Ok(TokenStream2::new()),
+    };
+
+    let b_ident = &method.sig.ident;
+    let ImplArgs { arg_tys, lifetimes, call_args } = impl_args(method, "operator impls")?;
+    if arg_tys.len() != 1 {
+        Err(format!("op only applies to methods with a single argument: {}", b_ident))?;
+    }
+    let (rhs_ty, rhs) = (&arg_tys[0], &call_args[0]);
+    let generics = impl_generics(generics, method, &lifetimes);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let cfgs = &std_impl_cfgs(method, macro_config, method_config)?;
+
+    let assign_trait = Ident::new(op_trait, Span::call_site());
+    let assign_fn = Ident::new(op_fn, Span::call_site());
+    let by_value_trait = Ident::new(op_trait.trim_end_matches("Assign"), Span::call_site());
+    let by_value_fn = Ident::new(op_fn.trim_end_matches("_assign"), Span::call_site());
+
+    Ok(quote! {
+        #(#cfgs)*
+        impl #impl_generics ::std::ops::#assign_trait<#rhs_ty> for #self_ty #where_clause {
+            fn #assign_fn(&mut self, #rhs: #rhs_ty) {
+                self.#b_ident(#rhs);
+            }
+        }
+
+        #(#cfgs)*
+        impl #impl_generics ::std::ops::#by_value_trait<#rhs_ty> for #self_ty #where_clause {
+            type Output = Self;
+
+            fn #by_value_fn(mut self, #rhs: #rhs_ty) -> Self {
+                self.#b_ident(#rhs);
+                self
+            }
+        }
+    })
+}
+
+// The argument types and call arguments of a `&mut self` method, for trait impls
+// generated by `options`.
+struct ImplArgs {
+    arg_tys: Vec<Type>,
+    // Elided lifetimes of argument types, named to be added to the impl generics
+    lifetimes: Vec<Lifetime>,
+    call_args: Punctuated<Expr, Comma>,
+}
+
+fn impl_args(method: &ImplItemMethod, options: &str) -> Result<ImplArgs, String> {
+    let b_ident = &method.sig.ident;
+    let err_msg = format!("{} require a `&mut self` method with arguments: {}", options, b_ident);
+    match method.sig.decl.inputs.first().map(|arg| arg.into_value()) {
+        Some(FnArg::SelfRef(arg)) if arg.mutability.is_some() => (),
+        _ => Err(&*err_msg)?,
+    }
+
+    let mut inputs = method.sig.decl.inputs.clone();
+    simplify_fn_args(&mut inputs);
+    let mut arg_tys = Vec::with_capacity(inputs.len());
+    for arg in method.sig.decl.inputs.iter().skip(1) {
+        match arg {
+            FnArg::Captured(ArgCaptured { ty: Type::ImplTrait(_), .. }) => {
+                Err(format!("{} don't support `impl Trait` parameters: {}", options, b_ident))?
+            },
+            FnArg::Captured(cap) => arg_tys.push(cap.ty.clone()),
+            _ => Err(&*err_msg)?,
+        }
+    }
+    if arg_tys.is_empty() {
+        Err(&*err_msg)?;
+    }
+
+    let mut lifetimes = Vec::new();
+    for arg_ty in &mut arg_tys {
+        t::name_elided_lifetimes(arg_ty, &mut lifetimes);
+    }
+
+    Ok(ImplArgs {
+        arg_tys,
+        lifetimes,
+        call_args: get_call_args(&inputs),
+    })
+}
+
+// Generic parameters of the impl block, followed by the ones of the method, and lifetimes named
+// by `impl_args()`. Lifetimes go first.
+fn impl_generics(generics: &Generics, method: &ImplItemMethod, named_lifetimes: &[Lifetime]) -> Generics {
+    let mut generics = generics.clone();
+    let (mut lifetimes, params): (Vec<_>, Vec<_>) = generics
+        .params
+        .iter()
+        .chain(&method.sig.decl.generics.params)
+        .cloned()
+        .partition(|param| match param {
+            GenericParam::Lifetime(_) => true,
+            _ => false,
+        });
+    lifetimes.extend(named_lifetimes.iter().map(|lifetime| -> GenericParam { parse_quote! { #lifetime } }));
+    generics.params = lifetimes.into_iter().chain(params).collect();
+    if let Some(where_clause) = &method.sig.decl.generics.where_clause {
+        generics.make_where_clause().predicates.extend(where_clause.predicates.iter().cloned());
+    }
+    generics
+}
+
+// A name not used by the arguments of a call.
+fn unused_ident(name: &str, call_args: &Punctuated<Expr, Comma>) -> Ident {
+    let arg_names: Vec<String> = call_args.iter().map(|arg| quote!(#arg).to_string()).collect();
+    let mut name = name.to_string();
+    while arg_names.contains(&name) {
+        name.push('_');
+    }
+    Ident::new(&name, Span::call_site())
+}
+
+// A trait method declaration matching a chaining method. Patterns are not
+// allowed in methods without a body, and arguments are already simplified.
+pub(crate) fn fluent_trait_decl(fluent_method: &ImplItemMethod) -> TraitItemMethod {
+    let mut sig = fluent_method.sig.clone();
+    if let Some(FnArg::SelfValue(arg)) = sig.decl.inputs.iter_mut().next() {
+        arg.mutability = None;
+    }
+    let attrs = fluent_method.attrs.iter().filter(|a| {
+        a.path == parse_quote! { doc }
+            || a.path == parse_quote! { cfg }
+            || a.path == parse_quote! { deprecated }
+            || a.path == parse_quote! { must_use }
+    });
+    parse_quote! { #(#attrs)* #sig; }
+}
+
 fn get_generic_params(generics: &Generics) -> Punctuated<Ident, Comma> {
     let mut ret = Punctuated::new();
     for param in &generics.params {
@@ -128,7 +914,12 @@ fn simplify_fn_args(inputs: &mut Punctuated<FnArg, Comma>) {
     for param in inputs.iter_mut().enumerate() {
         if let (idx, FnArg::Captured(cap)) = param {
             match cap.pat {
-                Pat::Ident(_) => (),
+                // Arguments are only passed along, `mut` and `ref` are not needed
+                Pat::Ident(ref mut pat) => {
+                    pat.by_ref = None;
+                    pat.mutability = None;
+                    pat.subpat = None;
+                },
                 _ => {
                     let ident = Ident::new(&format!("arg{}", idx), Span::call_site());
                     cap.pat = parse_quote! { #ident };
@@ -141,60 +932,322 @@ fn simplify_fn_args(inputs: &mut Punctuated<FnArg, Comma>) {
 fn get_call_args(inputs: &Punctuated<FnArg, Comma>) -> Punctuated<Expr, Comma> {
     let mut ret = Punctuated::new();
     for param in inputs {
-        match param {
-            FnArg::SelfRef(_) | FnArg::SelfValue(_) => (),
-            FnArg::Captured(cap) => {
+        if let Some(expr) = get_call_arg(param) {
+            ret.push_value(expr);
+            ret.push_punct(Token!(,)(Span::call_site()));
+        }
+    }
+    ret
+}
+
+fn get_call_arg(param: &FnArg) -> Option<Expr> {
+    match param {
+        FnArg::SelfRef(_) | FnArg::SelfValue(_) => None,
+        FnArg::Captured(cap) => {
+            let pat = &cap.pat;
+            Some(parse_quote! { #pat })
+        },
+        FnArg::Inferred(pat) => Some(parse_quote! { #pat }),
+        FnArg::Ignored(ty) => Some(parse_quote! { #ty }),
+    }
+}
+
+// Parameter options of a method, with the parameter of a flag always set to `true`
+// in the chaining (on) method.
+fn method_params(method: &ImplItemMethod, method_config: &MethodConfig) -> Result<Vec<(usize, ParamConfig)>, String> {
+    let mut params = method_config.params.clone();
+    if method_config.flag {
+        let inputs = &method.sig.decl.inputs;
+        match inputs.iter().nth(1) {
+            Some(FnArg::Captured(cap)) if inputs.len() == 2 && cap.ty == parse_quote! { bool } => (),
+            _ => Err(format!("flag only applies to methods with a single `bool` parameter: {}", method.sig.ident))?,
+        }
+        if !params.is_empty() {
+            Err("parameter options can't be set if flag is set")?;
+        }
+        params.push((1, ParamConfig { default: Some("true".into()), ..ParamConfig::default() }));
+    }
+    Ok(params)
+}
+
+// Apply parameter options to the (simplified) arguments of a chaining method, and
+// return the arguments it passes to the chain-able method.
+fn apply_param_configs(
+    inputs: &mut Punctuated<FnArg, Comma>,
+    params: &[(usize, ParamConfig)],
+) -> Result<Punctuated<Expr, Comma>, String> {
+    let mut fluent_inputs = Punctuated::new();
+    let mut call_args = Punctuated::new();
+
+    for (idx, mut param) in inputs.clone().into_iter().enumerate() {
+        let param_config = params.iter().find(|(i, _)| *i == idx).map(|(_, p)| p);
+        let call_arg = match (&mut param, param_config) {
+            (FnArg::Captured(cap), Some(param_config)) => {
                 let pat = &cap.pat;
-                let expr: Expr = parse_quote! { #pat };
-                ret.push_value(expr);
-                ret.push_punct(Token!(,)(Span::call_site()));
-            },
-            FnArg::Inferred(pat) => {
-                let expr: Expr = parse_quote! { #pat };
-                ret.push_value(expr);
-                ret.push_punct(Token!(,)(Span::call_site()));
-            },
-            FnArg::Ignored(ty) => {
-                let expr: Expr = parse_quote! { #ty };
-                ret.push_value(expr);
-                ret.push_punct(Token!(,)(Span::call_site()));
+                let mut call_arg: Expr = parse_quote! { #pat };
+                if let Some(default) = &param_config.default {
+                    // Parsed already in config
+                    call_args.push(syn::parse_str::<Expr>(default).map_err(|e| e.to_string())?);
+                    continue;
+                }
+                if param_config.into {
+                    call_arg = parse_quote! { #call_arg.into() };
+                }
+                if param_config.strip_option {
+                    let inner_ty = match t::option_inner_ty(&cap.ty) {
+                        Some(inner_ty) => inner_ty.clone(),
+                        None => Err(format!("strip_option only applies to `Option<T>` parameters, found: {}", quote!(#pat)))?,
+                    };
+                    cap.ty = inner_ty;
+                    call_arg = parse_quote! { Some(#call_arg) };
+                }
+                if param_config.into {
+                    let ty = &cap.ty;
+                    cap.ty = parse_quote! { impl Into<#ty> };
+                }
+                Some(call_arg)
             },
+            (_, Some(_)) => Err("parameter options only apply to `name: Type` parameters")?,
+            (param, None) => get_call_arg(param),
+        };
+        fluent_inputs.push(param);
+        if let Some(call_arg) = call_arg {
+            call_args.push(call_arg);
         }
     }
-    ret
+
+    *inputs = fluent_inputs;
+    Ok(call_args)
 }
 
-fn fluent_doc(method: &ImplItemMethod, macro_config: &MacroConfig) -> Result<String, String> {
-    let method_config = get_method_config(&method.attrs)?;
-    let mut doc = if let Some(doc) = method_config.doc {
-        doc
+// A link back to a chaining method, appended to the chain-able method doc.
+// `link_base` is where the chaining method is linked from (`Self` or the extension trait).
+pub(crate) fn fluent_see_also(fluent_method: &ImplItemMethod, link_base: &str) -> Vec<Attribute> {
+    let f_ident_str = fluent_method.sig.ident.to_string();
+    let see_also = format!(" See also: [`{}()`].", f_ident_str);
+    let link = format!(" [`{0}()`]: {1}::{0}", f_ident_str, link_base);
+    let lines = ["", &see_also, "", &link];
+
+    // Don't link to a chaining method that's configured out
+    let cfgs: Vec<NestedMeta> = fluent_method
+        .attrs
+        .iter()
+        .filter(|a| a.path == parse_quote! { cfg })
+        .filter_map(|a| match a.interpret_meta() {
+            Some(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    if cfgs.is_empty() {
+        lines.iter().map(|line| parse_quote! { #[doc = #line] }).collect()
     } else {
-        macro_config.doc.clone()
+        let cfgs = &cfgs;
+        lines.iter().map(|line| parse_quote! { #[cfg_attr(all(#(#cfgs),*), doc = #line)] }).collect()
+    }
+}
+
+fn fluent_doc(macro_config: &MacroConfig, method_config: &MethodConfig) -> String {
+    // Method-level doc wins over inherit_doc, then block-level inherit_doc wins over doc
+    match (&method_config.doc, method_config.inherit_doc || macro_config.inherit_doc) {
+        (Some(doc), _) => doc.clone(),
+        (None, true) => "%d%\n\n*The chaining (fluent) equivalent of [`%f%()`].*".into(),
+        (None, false) => macro_config.doc.clone(),
+    }
+}
+
+// A usage example showing the chaining method next to the chain-able one. With `no_run`,
+// hidden lines declaring the arguments let the example compile as a doc test.
+fn fluent_doc_example(
+    method: &ImplItemMethod,
+    f_ident: &Ident,
+    params: &[(usize, ParamConfig)],
+    macro_config: &MacroConfig,
+    method_config: &MethodConfig,
+    ty: &Type,
+) -> String {
+    let mode = match method_config.doc_example.as_ref().or(macro_config.doc_example.as_ref()) {
+        // The chain-able method of a nested or setter chaining method is an accessor
+        Some(_) if method_config.nested || is_setter(method, macro_config, method_config) => return String::new(),
+        // The chaining method is not a method of the type
+        Some(_) if macro_config.builder.is_some() => return String::new(),
+        Some(mode) => mode,
+        None => return String::new(),
     };
-    doc += "\n\n [`%f%`]: %t%::%f%";
-    doc += "\n [`%f%()`]: %t%::%f%";
-    Ok(doc)
+
+    let ty_name = t::ty_str(ty, false);
+    let init = match method_config.doc_example_init.as_ref().or(macro_config.doc_example_init.as_ref()) {
+        Some(init) => init.replace("%t%", &ty_name),
+        None => format!("{}::default()", ty_name),
+    };
+    let var = naming::make_ident(&naming::apply_case(&ty_name, "snake"))
+        .map(|ident| ident.to_string())
+        .unwrap_or_else(|_| "value".into());
+
+    let mut inputs = method.sig.decl.inputs.clone();
+    simplify_fn_args(&mut inputs);
+    let hidden_lets = match &**mode {
+        "no_run" => example_lets(&inputs, params),
+        _ => String::new(),
+    };
+    // Arguments are passed to the chain-able method as they are, not with `.into()`
+    let params: Vec<_> = params.iter().map(|(idx, p)| (*idx, ParamConfig { into: false, ..p.clone() })).collect();
+    let b_call_args = match apply_param_configs(&mut inputs, &params) {
+        Ok(b_call_args) => b_call_args,
+        // Reported when generating the chaining method
+        Err(_) => return String::new(),
+    };
+    let call_args = get_call_args(&inputs);
+    let args_str = |args: &Punctuated<Expr, Comma>| args.iter().map(|arg| t::tokens_str(quote! { #arg })).collect::<Vec<_>>().join(", ");
+
+    format!(
+        "\n\n```rust,{mode}\n{lets}let mut {var} = {init};\n{var}.{b}({b_args});\n\n// The same, chained\n{lets}let {var} = {init}.{f}({args});\n```",
+        mode = mode,
+        lets = hidden_lets,
+        var = var,
+        init = init,
+        b = method.sig.ident,
+        f = f_ident,
+        b_args = args_str(&b_call_args),
+        args = args_str(&call_args),
+    )
 }
 
-fn fluent_ident(method: &ImplItemMethod, macro_config: &MacroConfig) -> Result<String, String> {
-    let method_config = get_method_config(&method.attrs)?;
+// Hidden `let`s for the arguments of a `no_run` example. Arguments taken with `into` need
+// their types, which the chained call alone doesn't tell.
+fn example_lets(inputs: &Punctuated<FnArg, Comma>, params: &[(usize, ParamConfig)]) -> String {
+    let mut lets = String::new();
+    for (idx, arg) in inputs.iter().enumerate() {
+        let call_arg = match get_call_arg(arg) {
+            Some(call_arg) => t::tokens_str(quote! { #call_arg }),
+            None => continue,
+        };
+        match (arg, params.iter().find(|(i, _)| *i == idx).map(|(_, p)| p)) {
+            (_, Some(ParamConfig { default: Some(_), .. })) => (),
+            (FnArg::Captured(cap), Some(ParamConfig { into: true, strip_option, .. })) => {
+                let ty = match t::option_inner_ty(&cap.ty) {
+                    Some(inner_ty) if *strip_option => inner_ty,
+                    _ => &cap.ty,
+                };
+                lets += &format!("# let {}: {} = unimplemented!();\n", call_arg, t::tokens_str(quote! { #ty }));
+            },
+            _ => lets += &format!("# let {} = unimplemented!();\n", call_arg),
+        }
+    }
+    lets
+}
 
-    if let Some(name) = method_config.name {
-        return Ok(name);
+// Link definitions for the type and the chain-able method, so that `[`%t%`]`, `[`%f%`]` and
+// `[`%f%()`]` resolve. `Self` can't be used in an extension trait, where it's not the type.
+fn fluent_doc_links(b_ident: &Ident, macro_config: &MacroConfig, ty: &Type) -> String {
+    let link_base = match (&macro_config.trait_, &macro_config.builder) {
+        (None, None) => Some("Self".into()),
+        _ => t::ty_link_path(ty),
+    };
+    match link_base {
+        Some(link_base) => format!(
+            "\n\n [`{0}`]: {1}\n [`{2}`]: {1}::{2}\n [`{2}()`]: {1}::{2}",
+            t::ty_str(ty, macro_config.type_generics),
+            link_base,
+            b_ident
+        ),
+        None => String::new(),
     }
+}
+
+// The text of the doc comment of a method, without the leading space `///` leaves in every line.
+fn original_doc(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter(|a| a.path == parse_quote! { doc })
+        .filter_map(|a| match a.interpret_meta() {
+            Some(Meta::NameValue(MetaNameValue { lit: Lit::Str(ls), .. })) => Some(ls.value()),
+            _ => None,
+        })
+        .map(|line| match line.chars().next() {
+            Some(' ') => line[1..].to_string(),
+            _ => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-    let b_ident = if let Some(rename) = method_config.rename {
-        rename
+// The arguments of a method as written, without the receiver.
+fn args_str(inputs: &Punctuated<FnArg, Comma>) -> String {
+    inputs
+        .iter()
+        .filter(|arg| match arg {
+            FnArg::SelfRef(_) | FnArg::SelfValue(_) => false,
+            _ => true,
+        })
+        .map(|arg| t::tokens_str(quote! { #arg }))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn receiver_str(inputs: &Punctuated<FnArg, Comma>) -> String {
+    match inputs.iter().next() {
+        Some(arg @ FnArg::SelfRef(_)) | Some(arg @ FnArg::SelfValue(_)) => t::tokens_str(quote! { #arg }),
+        _ => String::new(),
+    }
+}
+
+fn fluent_ident(method: &ImplItemMethod, macro_config: &MacroConfig, method_config: &MethodConfig) -> Result<Ident, String> {
+    let method_name = naming::ident_name(&method.sig.ident);
+
+    if let Some(name) = &method_config.name {
+        return naming::make_ident(name);
+    }
+
+    let b_ident = fluent_base_name(method, macro_config, method_config);
+
+    // Method-level prefix or suffix override a block-level name template
+    let name_template = match (&method_config.name_template, &method_config.prefix, &method_config.suffix) {
+        (Some(name_template), _, _) => Some(name_template),
+        (None, None, None) => macro_config.name_template.as_ref(),
+        _ => None,
+    };
+
+    let ident_str = if let Some(name_template) = name_template {
+        name_template.replace("{}", &b_ident)
     } else {
-        method.sig.ident.to_string()
+        let prefix = method_config.prefix.as_ref().unwrap_or(&macro_config.prefix);
+        let suffix = method_config.suffix.as_ref().unwrap_or(&macro_config.suffix);
+        format!("{}{}{}", prefix, b_ident, suffix)
     };
 
-    let prefix = if let Some(prefix) = method_config.prefix {
-        prefix
+    // Builder methods are not methods of the type
+    if ident_str == method_name && macro_config.builder.is_none() {
+        Err(format!(
+            "the chaining method name of {} is the same as the chain-able method name",
+            method_name
+        ))?;
+    }
+    naming::make_ident(&ident_str)
+}
+
+// The chain-able method name (or rename) after stripping and case conversion.
+fn fluent_base_name(method: &ImplItemMethod, macro_config: &MacroConfig, method_config: &MethodConfig) -> String {
+    let method_name = naming::ident_name(&method.sig.ident);
+
+    let b_ident = if let Some(rename) = &method_config.rename {
+        rename.clone()
     } else {
-        macro_config.prefix.clone()
+        let strip_prefix = method_config.strip_prefix.as_ref().or(macro_config.strip_prefix.as_ref());
+        let b_ident = match strip_prefix.and_then(|strip_prefix| naming::strip_prefix(&method_name, strip_prefix)) {
+            Some(stripped) => stripped,
+            None => &*method_name,
+        };
+        // `with_tls()` for `tls_mut()`
+        let accessor = method_config.nested || is_setter(method, macro_config, method_config);
+        match naming::strip_suffix(b_ident, "_mut") {
+            Some(stripped) if accessor && !stripped.is_empty() => stripped.into(),
+            _ => b_ident.into(),
+        }
     };
 
-    let ident_str = prefix + &b_ident;
-    Ok(ident_str)
+    match method_config.case.as_ref().or(macro_config.case.as_ref()) {
+        Some(case) => naming::apply_case(&b_ident, case),
+        None => b_ident,
+    }
 }
@@ -0,0 +1,179 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use std::cell::Cell;
+
+use proc_macro2::Span;
+use syn::{self, Ident};
+
+thread_local! {
+    static UNIQUE_COUNT: Cell<usize> = Cell::new(0);
+}
+
+// Strict and reserved keywords in all editions. These need to be raw identifiers.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum",
+    "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move",
+    "mut", "override", "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+// Keywords that can't be raw identifiers.
+const NON_RAW_KEYWORDS: &[&str] = &["_", "crate", "self", "Self", "super"];
+
+// `str::strip_prefix()` is not available in our minimum Rust version.
+pub(crate) fn strip_prefix<'a>(name: &'a str, prefix: &str) -> Option<&'a str> {
+    if name.starts_with(prefix) {
+        Some(&name[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+// `str::strip_suffix()` is not available in our minimum Rust version either.
+pub(crate) fn strip_suffix<'a>(name: &'a str, suffix: &str) -> Option<&'a str> {
+    if name.ends_with(suffix) {
+        Some(&name[..name.len() - suffix.len()])
+    } else {
+        None
+    }
+}
+
+// A name for a generated helper item that doesn't clash with the ones of other expansions
+// in the same module.
+pub(crate) fn unique_ident(prefix: &str) -> Ident {
+    let count = UNIQUE_COUNT.with(|count| {
+        count.set(count.get() + 1);
+        count.get()
+    });
+    Ident::new(&format!("{}_{}", prefix, count), Span::call_site())
+}
+
+// The name of an identifier without the raw `r#` prefix.
+pub(crate) fn ident_name(ident: &Ident) -> String {
+    let name = ident.to_string();
+    strip_prefix(&name, "r#").map(String::from).unwrap_or(name)
+}
+
+// Create an identifier from a name, using a raw identifier if the name is a keyword.
+// Names already starting with `r#` are accepted.
+pub(crate) fn make_ident(name: &str) -> Result<Ident, String> {
+    let bare_name = strip_prefix(name, "r#").unwrap_or(name);
+
+    if bare_name.is_empty() {
+        Err("invalid empty method name")?;
+    }
+
+    if NON_RAW_KEYWORDS.contains(&bare_name) {
+        Err(format!("invalid method name, {} is a keyword", bare_name))?;
+    }
+
+    let ident_str = if KEYWORDS.contains(&bare_name) {
+        format!("r#{}", bare_name)
+    } else {
+        bare_name.into()
+    };
+
+    syn::parse_str::<Ident>(&ident_str).map_err(|_| format!("invalid method name: {:?}", name))
+}
+
+pub(crate) fn check_case(case: &str) -> Result<(), String> {
+    match case {
+        "snake" | "camel" | "lower" | "upper" => Ok(()),
+        _ => Err(format!(
+            "invalid case attribute: {:?}, expected one of \"snake\", \"camel\", \"lower\" or \"upper\"",
+            case
+        ))?,
+    }
+}
+
+// `option` is the name of the option the template is passed to.
+pub(crate) fn check_template(option: &str, template: &str) -> Result<(), String> {
+    if template.matches("{}").count() != 1 {
+        Err(format!("{} must contain exactly one {{}}, found: {:?}", option, template))?;
+    }
+    Ok(())
+}
+
+// A comma-separated list of method name patterns, where `*` matches any sequence of
+// characters and `?` matches a single character.
+pub(crate) fn parse_patterns(patterns: &str) -> Result<Vec<String>, String> {
+    let mut ret = Vec::new();
+    for pattern in patterns.split(',').map(str::trim) {
+        let valid_char = |c: char| c.is_alphanumeric() || c == '_' || c == '*' || c == '?';
+        if pattern.is_empty() || !pattern.chars().all(valid_char) {
+            Err(format!("invalid method name pattern {:?} in {:?}", pattern, patterns))?;
+        }
+        ret.push(pattern.into());
+    }
+    Ok(ret)
+}
+
+pub(crate) fn pattern_matches(pattern: &str, name: &str) -> bool {
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match (pattern.first(), name.first()) {
+            (None, None) => true,
+            (Some('*'), _) => matches(&pattern[1..], name) || (!name.is_empty() && matches(pattern, &name[1..])),
+            (Some('?'), Some(_)) => matches(&pattern[1..], &name[1..]),
+            (Some(p), Some(n)) if p == n => matches(&pattern[1..], &name[1..]),
+            _ => false,
+        }
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    matches(&pattern, &name)
+}
+
+pub(crate) fn apply_case(name: &str, case: &str) -> String {
+    match case {
+        "snake" => to_snake_case(name),
+        "camel" => to_camel_case(name),
+        "lower" => name.to_lowercase(),
+        "upper" => name.to_uppercase(),
+        _ => unreachable!(),
+    }
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut ret = String::with_capacity(name.len() + 4);
+    let mut prev_is_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() {
+            if prev_is_lower {
+                ret.push('_');
+            }
+            ret.extend(c.to_lowercase());
+            prev_is_lower = false;
+        } else {
+            ret.push(c);
+            prev_is_lower = c.is_lowercase() || c.is_numeric();
+        }
+    }
+    ret
+}
+
+fn to_camel_case(name: &str) -> String {
+    let mut ret = String::with_capacity(name.len());
+    let mut upper_next = false;
+    for c in name.chars() {
+        match c {
+            // Keep leading underscores
+            '_' if ret.chars().all(|c| c == '_') => ret.push(c),
+            '_' => upper_next = true,
+            c if upper_next => {
+                ret.extend(c.to_uppercase());
+                upper_next = false;
+            },
+            c => ret.push(c),
+        }
+    }
+    ret
+}
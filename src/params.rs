@@ -0,0 +1,130 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use std::collections::HashMap;
+
+use proc_macro2::{Delimiter, Group, Spacing, TokenStream as TokenStream2, TokenTree};
+use syn::{parse::Parser, Attribute};
+
+use config::{self, ParamConfig};
+
+// Parameter options by method name, see `MethodConfig::params`.
+pub(crate) type ParamConfigs = HashMap<String, Vec<(usize, ParamConfig)>>;
+
+// Take `#[fluent(...)]` attributes out of method parameters. syn can't parse
+// parameter attributes, and the compiler doesn't know this one anyway.
+pub(crate) fn take_param_configs(input: TokenStream2) -> Result<(TokenStream2, ParamConfigs), String> {
+    let mut param_configs = ParamConfigs::new();
+    let output = take_from_items(input, true, &mut param_configs)?;
+    Ok((output, param_configs))
+}
+
+// `in_impl` is set until an impl block body is entered. Method bodies are never entered.
+fn take_from_items(input: TokenStream2, in_impl: bool, param_configs: &mut ParamConfigs) -> Result<TokenStream2, String> {
+    let mut output = Vec::new();
+    let mut seen_fn = false;
+    let mut after_fn = false;
+    // Name of the current method, until its parameters are found
+    let mut fn_name: Option<String> = None;
+    let mut angle_depth = 0;
+    let mut prev_minus = false;
+
+    for tt in input {
+        let mut tt = tt;
+        match &tt {
+            TokenTree::Ident(ident) if ident == "fn" => {
+                seen_fn = true;
+                after_fn = true;
+            },
+            TokenTree::Ident(ident) if after_fn => {
+                fn_name = Some(ident.to_string());
+                after_fn = false;
+            },
+            // `->` in generic bounds like `F: Fn() -> u8`
+            TokenTree::Punct(p) if fn_name.is_some() && p.as_char() == '>' && !prev_minus => angle_depth -= 1,
+            TokenTree::Punct(p) if fn_name.is_some() && p.as_char() == '<' => angle_depth += 1,
+            TokenTree::Group(g) if g.delimiter() == Delimiter::Parenthesis && angle_depth == 0 && fn_name.is_some() => {
+                let (params, configs) = take_from_params(g.stream())?;
+                if let Some(fn_name) = fn_name.take() {
+                    if !configs.is_empty() {
+                        param_configs.insert(fn_name, configs);
+                    }
+                }
+                let mut group = Group::new(Delimiter::Parenthesis, params);
+                group.set_span(g.span());
+                tt = TokenTree::Group(group);
+            },
+            TokenTree::Group(g) if g.delimiter() == Delimiter::Brace && in_impl && !seen_fn => {
+                let mut group = Group::new(Delimiter::Brace, take_from_items(g.stream(), false, param_configs)?);
+                group.set_span(g.span());
+                tt = TokenTree::Group(group);
+            },
+            _ => after_fn = false,
+        }
+        prev_minus = match &tt {
+            TokenTree::Punct(p) => p.as_char() == '-' && p.spacing() == Spacing::Joint,
+            _ => false,
+        };
+        output.push(tt);
+    }
+
+    Ok(output.into_iter().collect())
+}
+
+fn take_from_params(input: TokenStream2) -> Result<(TokenStream2, Vec<(usize, ParamConfig)>), String> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let mut output = Vec::with_capacity(tokens.len());
+    let mut configs: Vec<(usize, ParamConfig)> = Vec::new();
+    let mut param_idx = 0;
+    let mut angle_depth = 0;
+    let mut prev_minus = false;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match (&tokens[i], tokens.get(i + 1)) {
+            (TokenTree::Punct(p), Some(TokenTree::Group(g))) if p.as_char() == '#' && is_fluent_attr(g) => {
+                let attr_tokens: TokenStream2 = tokens[i..i + 2].iter().cloned().collect();
+                let attrs = Attribute::parse_outer.parse2(attr_tokens).map_err(|e| e.to_string())?;
+                for attr in &attrs {
+                    let attr_info = config::parse_config_from_attr(attr)?;
+                    match configs.iter_mut().find(|(idx, _)| *idx == param_idx) {
+                        Some((_, config)) => *config = config::get_param_config(attr_info, Some(config.clone()))?,
+                        None => configs.push((param_idx, config::get_param_config(attr_info, None)?)),
+                    }
+                }
+                i += 2;
+                continue;
+            },
+            (TokenTree::Punct(p), _) if p.as_char() == ',' && angle_depth == 0 => param_idx += 1,
+            (TokenTree::Punct(p), _) if p.as_char() == '<' => angle_depth += 1,
+            (TokenTree::Punct(p), _) if p.as_char() == '>' && !prev_minus => angle_depth -= 1,
+            _ => (),
+        }
+        prev_minus = match &tokens[i] {
+            TokenTree::Punct(p) => p.as_char() == '-' && p.spacing() == Spacing::Joint,
+            _ => false,
+        };
+        output.push(tokens[i].clone());
+        i += 1;
+    }
+
+    Ok((output.into_iter().collect(), configs))
+}
+
+fn is_fluent_attr(group: &Group) -> bool {
+    if group.delimiter() != Delimiter::Bracket {
+        return false;
+    }
+    match group.stream().into_iter().next() {
+        Some(TokenTree::Ident(ident)) => ident == "fluent",
+        _ => false,
+    }
+}
@@ -9,13 +9,218 @@
     file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
 */
 
-use syn::Type;
-
-pub(crate) fn bare_ty_str(ty: &Type) -> Result<String, String> {
-    (quote! { #ty })
-        .to_string()
-        .split(char::is_whitespace)
-        .nth(0)
-        .map(|s| s.into())
-        .ok_or_else(|| "impossible".into())
+use std::iter;
+
+use proc_macro2::{Delimiter, Group, Spacing, Span, TokenStream as TokenStream2, TokenTree};
+use quote::ToTokens;
+use syn::{
+    self,
+    parse::Parse,
+    GenericArgument, GenericParam, Generics, Lifetime, PathArguments, Type, TypeArray, TypeGroup, TypeParen, TypePath, TypePtr,
+    TypeReference, TypeSlice, TypeTuple,
+};
+
+// The name of a type as shown in docs. For paths, that's the last segment,
+// with or without its generic arguments.
+pub(crate) fn ty_str(ty: &Type, generics: bool) -> String {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => match path.segments.iter().last() {
+            Some(segment) if generics => tokens_str(quote! { #segment }),
+            Some(segment) => segment.ident.to_string(),
+            None => tokens_str(quote! { #ty }),
+        },
+        Type::Group(TypeGroup { elem, .. }) | Type::Paren(TypeParen { elem, .. }) => ty_str(elem, generics),
+        _ => tokens_str(quote! { #ty }),
+    }
+}
+
+// A path to a type usable in intra-doc links, i.e. without generic arguments.
+// Only paths can be linked to.
+pub(crate) fn ty_link_path(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => {
+            let mut path = path.clone();
+            for segment in path.segments.iter_mut() {
+                segment.arguments = PathArguments::None;
+            }
+            Some(tokens_str(quote! { #path }))
+        },
+        Type::Group(TypeGroup { elem, .. }) | Type::Paren(TypeParen { elem, .. }) => ty_link_path(elem),
+        _ => None,
+    }
+}
+
+// `T` in `Option<T>`. Only the last path segment is checked, like in `Option<T>`,
+// `option::Option<T>` or `std::option::Option<T>`.
+pub(crate) fn option_inner_ty(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => {
+            let segment = path.segments.iter().last()?;
+            match &segment.arguments {
+                PathArguments::AngleBracketed(args) if segment.ident == "Option" && args.args.len() == 1 => {
+                    match args.args.first()?.into_value() {
+                        GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    }
+                },
+                _ => None,
+            }
+        },
+        Type::Group(TypeGroup { elem, .. }) | Type::Paren(TypeParen { elem, .. }) => option_inner_ty(elem),
+        _ => None,
+    }
+}
+
+// Generic parameters as arguments, e.g. `'a`, `T` for `<'a, T: Clone>`.
+pub(crate) fn generic_args(generics: &Generics) -> Vec<TokenStream2> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(l) => {
+                let lifetime = &l.lifetime;
+                quote! { #lifetime }
+            },
+            GenericParam::Type(t) => {
+                let ident = &t.ident;
+                quote! { #ident }
+            },
+            GenericParam::Const(c) => {
+                let ident = &c.ident;
+                quote! { #ident }
+            },
+        })
+        .collect()
+}
+
+// Name elided lifetimes of a type (`&T`, `&'_ T`, `Cow<'_, T>`), which are not allowed in
+// impl headers. Lifetimes named here are appended to `lifetimes`.
+pub(crate) fn name_elided_lifetimes(ty: &mut Type, lifetimes: &mut Vec<Lifetime>) {
+    fn named(lifetimes: &mut Vec<Lifetime>) -> Lifetime {
+        let lifetime = Lifetime::new(&format!("'__fluent{}", lifetimes.len()), Span::call_site());
+        lifetimes.push(lifetime.clone());
+        lifetime
+    }
+
+    match ty {
+        Type::Reference(TypeReference { lifetime, elem, .. }) => {
+            let elided = match lifetime {
+                Some(lifetime) => lifetime.ident == "_",
+                None => true,
+            };
+            if elided {
+                *lifetime = Some(named(lifetimes));
+            }
+            name_elided_lifetimes(elem, lifetimes);
+        },
+        Type::Path(TypePath { qself, path }) => {
+            if let Some(qself) = qself {
+                name_elided_lifetimes(&mut qself.ty, lifetimes);
+            }
+            for segment in path.segments.iter_mut() {
+                if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                    for arg in args.args.iter_mut() {
+                        match arg {
+                            GenericArgument::Lifetime(lifetime) if lifetime.ident == "_" => *lifetime = named(lifetimes),
+                            GenericArgument::Type(ty) => name_elided_lifetimes(ty, lifetimes),
+                            GenericArgument::Binding(binding) => name_elided_lifetimes(&mut binding.ty, lifetimes),
+                            _ => (),
+                        }
+                    }
+                }
+            }
+        },
+        Type::Tuple(TypeTuple { elems, .. }) => {
+            for elem in elems.iter_mut() {
+                name_elided_lifetimes(elem, lifetimes);
+            }
+        },
+        Type::Slice(TypeSlice { elem, .. })
+        | Type::Array(TypeArray { elem, .. })
+        | Type::Ptr(TypePtr { elem, .. })
+        | Type::Group(TypeGroup { elem, .. })
+        | Type::Paren(TypeParen { elem, .. }) => name_elided_lifetimes(elem, lifetimes),
+        _ => (),
+    }
+}
+
+// `node` with `Self` replaced by `self_ty`, for items moved to an impl block of another
+// type. `Self::Assoc` becomes `<Ty>::Assoc`.
+pub(crate) fn replace_self<T: ToTokens + Parse>(node: &T, self_ty: &Type) -> Result<T, String> {
+    fn replace(tokens: TokenStream2, self_ty: &Type) -> TokenStream2 {
+        let mut ret = TokenStream2::new();
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(tt) = tokens.next() {
+            match tt {
+                TokenTree::Ident(ref ident) if ident == "Self" => {
+                    let path_sep = match tokens.peek() {
+                        Some(TokenTree::Punct(p)) => p.as_char() == ':' && p.spacing() == Spacing::Joint,
+                        _ => false,
+                    };
+                    if path_sep {
+                        ret.extend(quote! { <#self_ty> });
+                    } else {
+                        self_ty.to_tokens(&mut ret);
+                    }
+                },
+                TokenTree::Group(ref group) => {
+                    let mut new_group = Group::new(group.delimiter(), replace(group.stream(), self_ty));
+                    new_group.set_span(group.span());
+                    ret.extend(iter::once(TokenTree::Group(new_group)));
+                },
+                tt => ret.extend(iter::once(tt)),
+            }
+        }
+        ret
+    }
+
+    syn::parse2(replace(node.into_token_stream(), self_ty)).map_err(|e| e.to_string())
+}
+
+// A readable string of tokens, e.g. `x: &mut Vec<i32>` instead of `x : & mut Vec < i32 >`.
+pub(crate) fn tokens_str(tokens: TokenStream2) -> String {
+    let mut ret = String::new();
+    // Previous token, and whether it's the second punct of `::`
+    let mut prev: Option<TokenTree> = None;
+    let mut prev_path_sep = false;
+
+    for tt in tokens {
+        let space = match (&prev, &tt) {
+            (None, _) => false,
+            (Some(TokenTree::Punct(p)), _) if p.spacing() == Spacing::Joint || prev_path_sep => false,
+            (Some(TokenTree::Punct(p)), _) if p.as_char() == '&' || p.as_char() == '<' => false,
+            (_, TokenTree::Punct(p)) if ",;:>".contains(p.as_char()) => false,
+            (Some(TokenTree::Ident(_)), TokenTree::Punct(p)) if p.as_char() == '<' => false,
+            (Some(TokenTree::Ident(i)), TokenTree::Group(g)) if g.delimiter() != Delimiter::Brace => {
+                i == "mut" || i == "dyn" || i == "impl"
+            },
+            _ => true,
+        };
+        if space {
+            ret.push(' ');
+        }
+
+        prev_path_sep = match (&prev, &tt) {
+            (Some(TokenTree::Punct(p1)), TokenTree::Punct(p2)) => {
+                p1.as_char() == ':' && p1.spacing() == Spacing::Joint && p2.as_char() == ':'
+            },
+            _ => false,
+        };
+
+        match &tt {
+            TokenTree::Group(g) => {
+                let inner = tokens_str(g.stream());
+                match g.delimiter() {
+                    Delimiter::Parenthesis => ret += &format!("({})", inner),
+                    Delimiter::Bracket => ret += &format!("[{}]", inner),
+                    Delimiter::Brace => ret += &format!("{{ {} }}", inner),
+                    Delimiter::None => ret += &inner,
+                }
+            },
+            tt => ret += &tt.to_string(),
+        }
+        prev = Some(tt);
+    }
+
+    ret
 }
@@ -0,0 +1,29 @@
+#![deny(unused_must_use)]
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::fluent_impl;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    #[fluent_impl]
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        pub fn add_1(&mut self) {
+            self.num += 1;
+        }
+    }
+}
+
+fn main() {
+    use simple::Simple;
+    let s = Simple::default();
+    s.clone().with_add_1(); //~ ERROR unused return value of `Simple::with_add_1` that must be used
+    assert_eq!(s.get_num(), 0);
+}
@@ -0,0 +1,23 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::fluent_impl;
+
+    #[derive(Default)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    #[fluent_impl(builder = "SimpleBuilder", validate = "Self::validate")] //~ ERROR
+    impl Simple {
+        pub fn set_num(&mut self, num: i32) {
+            self.num = num;
+        }
+
+        fn validate(&self) -> Result<(), String> {
+            Ok(())
+        }
+    }
+}
+
+fn main() {}
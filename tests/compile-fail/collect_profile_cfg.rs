@@ -0,0 +1,26 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        items: Vec<String>,
+    }
+
+    // Extend impls are generated once, the profiles disagree on their cfg
+    #[fluent_impl(profile = "owned")] //~ ERROR
+    #[fluent_impl(profile = "ext", trait = "SimpleExt", cfg = "all()")]
+    impl Simple {
+        #[fluent_impl_opts(extend)]
+        pub fn push(&mut self, item: String) {
+            self.items.push(item);
+        }
+    }
+}
+
+fn main() {
+    use simple::Simple;
+    let mut s = Simple::default();
+    s.extend(Some("a".to_string()));
+}
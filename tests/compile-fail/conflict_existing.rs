@@ -0,0 +1,26 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::fluent_impl;
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        v: Vec<u32>,
+    }
+
+    #[fluent_impl] //~ ERROR
+    impl Simple {
+        pub fn with_capacity(n: usize) -> Self {
+            Self { v: Vec::with_capacity(n) }
+        }
+
+        pub fn capacity(&mut self) {
+            self.v.shrink_to_fit();
+        }
+    }
+}
+
+fn main() {
+    use simple::Simple;
+    let _ = Simple::with_capacity(1);
+}
@@ -0,0 +1,27 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    #[fluent_impl] //~ ERROR
+    impl Simple {
+        pub fn add_1(&mut self) {
+            self.num += 1;
+        }
+
+        #[fluent_impl_opts(rename = "add_1")]
+        pub fn add_one(&mut self) {
+            self.num += 1;
+        }
+    }
+}
+
+fn main() {
+    use simple::Simple;
+    let _ = Simple::default().with_add_1();
+}
@@ -0,0 +1,28 @@
+#![deny(deprecated)]
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    #[fluent_impl]
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        #[fluent_impl_opts(rename = "added_1", deprecated_alias = "with_add_1")]
+        pub fn add_1(&mut self) {
+            self.num += 1;
+        }
+    }
+}
+
+fn main() {
+    use simple::Simple;
+    assert_eq!(Simple::default().with_add_1().get_num(), 1); //~ ERROR use of deprecated method
+}
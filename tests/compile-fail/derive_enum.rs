@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate fluent_impl;
+
+#[derive(Fluent)] //~ ERROR
+pub enum Simple {
+    A,
+    B,
+}
+
+fn main() {}
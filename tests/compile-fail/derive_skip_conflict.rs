@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate fluent_impl;
+
+#[derive(Fluent)] //~ ERROR
+pub struct Simple {
+    #[fluent(skip, into)]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default)]
+    pub struct Simple {
+        items: Vec<String>,
+    }
+
+    #[fluent_impl(profile = "ext", trait = "SimpleExt")] //~ ERROR
+    impl Simple {
+        #[fluent_impl_opts(profile = "ext", extend)]
+        pub fn push(&mut self, item: String) {
+            self.items.push(item);
+        }
+    }
+}
+
+fn main() {}
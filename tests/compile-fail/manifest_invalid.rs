@@ -0,0 +1,27 @@
+// rustc-env:CARGO_MANIFEST_DIR=tests/manifest/invalid
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::fluent_impl;
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    #[fluent_impl] //~ ERROR
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        pub fn add_1(&mut self) {
+            self.num += 1;
+        }
+    }
+}
+
+fn main() {
+    use simple::Simple;
+    assert_eq!(Simple::default().with_add_1().get_num(), 1);
+}
@@ -0,0 +1,20 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    #[fluent_impl] //~ ERROR
+    impl Simple {
+        #[fluent_impl_opts(nested)]
+        pub fn num(&self) -> &i32 {
+            &self.num
+        }
+    }
+}
+
+fn main() {}
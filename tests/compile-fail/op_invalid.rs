@@ -0,0 +1,20 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    #[fluent_impl] //~ ERROR
+    impl Simple {
+        #[fluent_impl_opts(op = "Add")]
+        pub fn add(&mut self, num: i32) {
+            self.num += num;
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+extern crate fluent_impl;
+
+pub mod m {
+    use fluent_impl::fluent_impl;
+
+    #[derive(Default)]
+    pub struct Simple {
+        name: String,
+    }
+
+    #[fluent_impl] //~ ERROR
+    impl Simple {
+        pub fn set_name(&mut self, #[fluent(into, default = "String::new()")] name: String) {
+            self.name = name;
+        }
+    }
+}
+
+fn main() {}
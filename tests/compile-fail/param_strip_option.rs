@@ -0,0 +1,19 @@
+extern crate fluent_impl;
+
+pub mod m {
+    use fluent_impl::fluent_impl;
+
+    #[derive(Default)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    #[fluent_impl] //~ ERROR
+    impl Simple {
+        pub fn set_num(&mut self, #[fluent(strip_option)] num: i32) {
+            self.num = num;
+        }
+    }
+}
+
+fn main() {}
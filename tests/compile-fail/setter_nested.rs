@@ -0,0 +1,20 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default)]
+    pub struct Simple {
+        nums: Vec<i32>,
+    }
+
+    #[fluent_impl] //~ ERROR
+    impl Simple {
+        #[fluent_impl_opts(nested, setter)]
+        pub fn nums_mut(&mut self) -> &mut Vec<i32> {
+            &mut self.nums
+        }
+    }
+}
+
+fn main() {}
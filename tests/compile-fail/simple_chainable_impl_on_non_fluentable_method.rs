@@ -1,7 +1,7 @@
 extern crate fluent_impl;
 
 pub mod simple {
-    use fluent_impl::{fluent_impl, fluent_impl_opts};
+    use fluent_impl::fluent_impl;
 
     #[derive(Default, PartialEq, Debug)]
     pub struct Simple {
@@ -18,8 +18,9 @@ pub mod simple {
         }
 
         #[fluent_impl] //~ ERROR
-        pub fn add_1(&mut self) {
+        pub fn add_1(&mut self) -> i32 {
             self.num += 1;
+            self.num
         }
     }
 }
@@ -29,5 +30,4 @@ fn main() {
     let mut s1 = Simple::default();
     s1.add_1();
     assert_eq!(s1.get_num(), 1);
-    assert_eq!(s1, Simple::default().with_add_1());
 }
@@ -0,0 +1,29 @@
+extern crate fluent_impl;
+
+pub mod request {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default)]
+    pub struct Request {
+        url: String,
+        method: String,
+    }
+
+    #[fluent_impl(builder = "RequestBuilder", builder_default)]
+    impl Request {
+        #[fluent_impl_opts(required)]
+        pub fn set_url(&mut self, url: &str) {
+            self.url = url.into();
+        }
+
+        #[fluent_impl_opts(required)]
+        pub fn set_method(&mut self, method: &str) {
+            self.method = method.into();
+        }
+    }
+}
+
+fn main() {
+    use request::RequestBuilder;
+    let _ = RequestBuilder::default().with_set_url("http://localhost").build(); //~ ERROR
+}
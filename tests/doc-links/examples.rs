@@ -0,0 +1,39 @@
+// Generated `no_run` usage examples of this crate are compiled as doc tests.
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default)]
+    pub struct Simple {
+        num: i32,
+        names: Vec<String>,
+    }
+
+    #[fluent_impl(doc_example = "no_run", doc_example_init = "examples::simple::%t%::default()")]
+    impl Simple {
+        pub fn add(&mut self, step: i32) {
+            self.num += step;
+        }
+
+        // Arguments moved into the call
+        pub fn add_name(&mut self, name: String, (first, _): (bool, bool)) {
+            if first {
+                self.names.insert(0, name);
+            } else {
+                self.names.push(name);
+            }
+        }
+
+        // Parameter options
+        pub fn set_label(&mut self, #[fluent(into)] label: String, #[fluent(strip_option)] num: Option<i32>, #[fluent(default = "false")] first: bool) {
+            self.add_name(label, (first, first));
+            self.num = num.unwrap_or_default();
+        }
+
+        #[fluent_impl_opts(doc_example)]
+        pub fn clear(&mut self) {
+            self.num = 0;
+        }
+    }
+}
@@ -0,0 +1,90 @@
+// Generated docs of this crate are checked for broken intra-doc links.
+extern crate fluent_impl;
+
+pub mod net {
+    #[derive(Default)]
+    pub struct Conn {
+        retries: u32,
+    }
+
+    #[derive(Default)]
+    pub struct Wrapper<T> {
+        inner: Vec<T>,
+    }
+}
+
+pub mod chain {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    // Qualified self type, with a deprecated prefix and back-links
+    #[fluent_impl(deprecated_prefix = "and_", see_also, if_variant, opt_variant, each)]
+    impl ::net::Conn {
+        /// Set the number of retries.
+        pub fn set_retries(&mut self, retries: u32) {
+            self.retries = retries;
+        }
+
+        #[fluent_impl_opts(rename = "retry", deprecated_alias = "with_more_retries")]
+        pub fn add_retry(&mut self) {
+            self.retries += 1;
+        }
+    }
+
+    // Generic self type, in a trait profile
+    #[fluent_impl(trait = "WrapperExt", see_also, type_generics)]
+    impl<T: Clone> ::net::Wrapper<T> {
+        pub fn push(&mut self, item: &T) {
+            self.inner.push(item.clone());
+        }
+
+        #[fluent_impl_opts(preset(name = "with_nothing", args = "&[]"))]
+        pub fn extend(&mut self, items: &[T]) {
+            self.inner.extend_from_slice(items);
+        }
+
+        #[fluent_impl_opts(nested_chain)]
+        pub fn inner_mut(&mut self) -> &mut Vec<T> {
+            &mut self.inner
+        }
+
+        #[fluent_impl_opts(flag(off = "without_clear"))]
+        pub fn clear(&mut self, clear: bool) {
+            if clear {
+                self.inner.clear();
+            }
+        }
+    }
+
+    #[fluent_impl(inherit_doc, doc = "See [`%t%`] and [`%f%()`].")]
+    impl<'a> ::net::Wrapper<&'a str> {
+        /// Push a borrowed string.
+        pub fn push_str(&mut self, item: &'a str) {
+            self.inner.push(item);
+        }
+    }
+
+    // Typestate builder profile, with a generic self type
+    #[fluent_impl(builder = "WrapperBuilder", see_also, validate = "Self::check", validate_error = "String")]
+    impl<T: Clone + Default> ::net::Wrapper<Option<T>> {
+        /// Push an item, or nothing.
+        #[fluent_impl_opts(required)]
+        pub fn push_opt(&mut self, item: Option<T>) {
+            self.inner.push(item);
+        }
+
+        pub fn check(&self) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    pub struct Plain {
+        pub num: i32,
+    }
+
+    impl Plain {
+        #[fluent_impl(see_also)]
+        pub fn add(&mut self, step: i32) {
+            self.num += step;
+        }
+    }
+}
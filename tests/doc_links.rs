@@ -0,0 +1,73 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+// The most recently built fluent_impl proc-macro library (see the E0464 note in compiletest.rs).
+fn proc_macro_lib(deps: &Path) -> PathBuf {
+    fs::read_dir(deps)
+        .expect("failed to read target/debug/deps")
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+            file_name.contains("fluent_impl-")
+                && [".so", ".dylib", ".dll"].iter().any(|ext| file_name.ends_with(ext))
+        })
+        .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .expect("fluent_impl proc-macro library not found")
+}
+
+struct Paths {
+    deps: PathBuf,
+    fixtures: PathBuf,
+    out_dir: PathBuf,
+}
+
+fn paths() -> Paths {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    Paths {
+        deps: manifest_dir.join("target/debug/deps"),
+        fixtures: manifest_dir.join("tests/doc-links"),
+        out_dir: env::temp_dir().join("fluent_impl_doc_links"),
+    }
+}
+
+// Run `rustdoc` (or `rustc`) on a fixture crate using fluent_impl.
+fn run(tool: &str, file: &str, args: &[&str]) -> Output {
+    let paths = paths();
+    let tool_env = tool.to_uppercase();
+
+    Command::new(env::var(&tool_env).unwrap_or_else(|_| tool.into()))
+        .args(["--edition", "2015", "--crate-type", "lib"])
+        .arg("-L")
+        .arg(&paths.deps)
+        .arg("--extern")
+        .arg(format!("fluent_impl={}", proc_macro_lib(&paths.deps).display()))
+        .args(args)
+        .arg(paths.fixtures.join(file))
+        .output()
+        .expect("failed to run the compiler")
+}
+
+#[test]
+fn doc_links_resolve() {
+    let out_dir = paths().out_dir.join("doc");
+    let output = run(
+        "rustdoc",
+        "links.rs",
+        &["-D", "rustdoc::broken_intra_doc_links", "-o", out_dir.to_str().unwrap()],
+    );
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn doc_examples_compile() {
+    let out_dir = paths().out_dir.join("examples");
+    let output = run("rustc", "examples.rs", &["--out-dir", out_dir.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let rlib = format!("examples={}", out_dir.join("libexamples.rlib").display());
+    let output = run("rustdoc", "examples.rs", &["--test", "--extern", &rlib]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+}
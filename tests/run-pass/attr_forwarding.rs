@@ -0,0 +1,53 @@
+#![deny(deprecated, unused_must_use)]
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    #[fluent_impl(forward_attrs = "allow", add_attrs = "#[cold]")]
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        // Not forwarded, a second symbol with the same name would fail to link
+        #[export_name = "simple_add_1"]
+        pub extern "C" fn add_1(&mut self) {
+            self.num += 1;
+        }
+
+        #[deprecated(note = "use add_1 twice")]
+        #[allow(unused_variables)]
+        pub fn add_2(&mut self) {
+            let unused = 0;
+            self.num += 2;
+        }
+
+        #[cfg(all())]
+        #[fluent_impl_opts(drop_attrs = "inline, must_use", add_attrs = "#[allow(unused_mut)] #[inline(never)]")]
+        #[inline]
+        pub fn add_3(&mut self) {
+            self.num += 3;
+        }
+
+        #[cfg(any())]
+        pub fn add_4(&mut self) {
+            self.num += 4;
+        }
+    }
+}
+
+fn main() {
+    use simple::Simple;
+    let s = Simple::default().with_add_1().with_add_3();
+    // must_use is dropped for this chaining method
+    s.clone().with_add_3();
+    #[allow(deprecated)]
+    let s = s.with_add_2();
+    assert_eq!(s.get_num(), 6);
+}
@@ -0,0 +1,106 @@
+extern crate fluent_impl;
+
+pub mod server {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Server {
+        port: u16,
+        host: String,
+        workers: Vec<String>,
+        tls: bool,
+    }
+
+    #[fluent_impl(builder = "ServerBuilder", builder_default, validate = "Self::validate", validate_error = "String")]
+    impl Server {
+        pub fn set_port(&mut self, port: u16) {
+            self.port = port;
+        }
+
+        pub fn set_host(&mut self, #[fluent(into)] host: String) {
+            self.host = host;
+        }
+
+        #[fluent_impl_opts(nested)]
+        pub fn workers_mut(&mut self) -> &mut Vec<String> {
+            &mut self.workers
+        }
+
+        // `Self` is the built type, not the builder
+        pub fn set_port_from<F: Fn(&Self) -> u16>(&mut self, f: F) {
+            self.port = f(self);
+        }
+
+        #[fluent_impl_opts(flag(on = "with_tls", off = "without_tls"))]
+        pub fn set_tls(&mut self, tls: bool) {
+            self.tls = tls;
+        }
+
+        // Not on the builder
+        pub fn port(&self) -> u16 {
+            self.port
+        }
+
+        fn validate(&self) -> Result<(), String> {
+            match self.port {
+                0 => Err("port is not set".into()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    // No Default impl, built from a value
+    #[derive(PartialEq, Debug)]
+    pub struct Endpoint {
+        pub url: String,
+    }
+
+    #[fluent_impl(builder = "EndpointBuilder")]
+    impl Endpoint {
+        pub fn set_url(&mut self, url: &str) {
+            self.url = url.into();
+        }
+    }
+
+    #[derive(PartialEq, Debug)]
+    pub struct Pair<T>(pub T, pub T);
+
+    #[fluent_impl(builder = "PairBuilder", finish = "finish", prefix = "")]
+    impl<T> Pair<T>
+    where
+        T: Clone,
+    {
+        pub fn set_both(&mut self, v: T) {
+            self.0 = v.clone();
+            self.1 = v;
+        }
+
+        pub fn set_from(&mut self, other: &Self) {
+            self.0 = other.0.clone();
+            self.1 = other.1.clone();
+        }
+    }
+}
+
+fn main() {
+    use server::{Endpoint, EndpointBuilder, Pair, PairBuilder, ServerBuilder};
+    let server = ServerBuilder::default()
+        .with_set_port(8080)
+        .with_set_host("localhost")
+        .with_workers(|w| w.push("a".into()))
+        .with_tls()
+        .without_tls()
+        .build()
+        .unwrap();
+    assert_eq!(server.port(), 8080);
+    assert!(ServerBuilder::default().build().is_err());
+    assert_eq!(ServerBuilder::from(server).with_set_port(1).build().unwrap().port(), 1);
+    let server = ServerBuilder::default().with_set_port(2).with_set_port_from(|s| s.port() * 2).build();
+    assert_eq!(server.unwrap().port(), 4);
+
+    let endpoint = Endpoint { url: "a".into() };
+    assert_eq!(EndpointBuilder::from(endpoint).with_set_url("b").build().url, "b");
+
+    assert_eq!(PairBuilder::from(Pair(1, 2)).set_both(3).finish(), Pair(3, 3));
+    assert_eq!(PairBuilder::from(Pair(1, 2)).set_from(&Pair(4, 5)).finish(), Pair(4, 5));
+}
@@ -0,0 +1,72 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    // Configured out, so `Fallback` methods are used instead
+    #[fluent_impl(cfg = "any()", doc_cfg)]
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        pub fn add_1(&mut self) {
+            self.num += 1;
+        }
+    }
+
+    #[fluent_impl(cfg = "all()", doc_cfg, inblock, see_also)]
+    impl Simple {
+        pub fn add_2(&mut self) {
+            self.num += 2;
+        }
+
+        #[fluent_impl_opts(cfg = "any()", vis = "pub")]
+        pub fn add_3(&mut self) {
+            self.num += 3;
+        }
+    }
+
+    #[fluent_impl(trait = "SimpleExt", prefix = "and_", cfg = "all(not(any()))", doc_cfg)]
+    impl Simple {
+        #[fluent_impl_opts(cfg = "any()", doc_cfg)]
+        pub fn add_4(&mut self) {
+            self.num += 4;
+        }
+
+        pub fn add_5(&mut self) {
+            self.num += 5;
+        }
+    }
+
+    pub trait Fallback {
+        fn with_add_1(self) -> Self;
+        fn with_add_3(self) -> Self;
+        fn and_add_4(self) -> Self;
+    }
+
+    impl Fallback for Simple {
+        fn with_add_1(self) -> Self {
+            self
+        }
+
+        fn with_add_3(self) -> Self {
+            self
+        }
+
+        fn and_add_4(self) -> Self {
+            self
+        }
+    }
+}
+
+fn main() {
+    use simple::{Fallback, Simple, SimpleExt};
+    let s = Simple::default().with_add_1().with_add_2().with_add_3().and_add_4().and_add_5();
+    assert_eq!(s.get_num(), 7);
+}
@@ -0,0 +1,129 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+    use std::collections::BTreeMap;
+    use std::fmt::Debug;
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        items: Vec<String>,
+        vars: BTreeMap<String, i32>,
+    }
+
+    #[fluent_impl]
+    impl Simple {
+        #[fluent_impl_opts(extend, from_iter)]
+        pub fn push(&mut self, item: String) {
+            self.items.push(item);
+        }
+
+        // Generic method, multiple arguments
+        #[fluent_impl_opts(extend)]
+        pub fn set_var<K>(&mut self, key: K, val: i32)
+        where
+            K: Into<String>,
+        {
+            self.vars.insert(key.into(), val);
+        }
+
+        // Elided lifetimes
+        #[fluent_impl_opts(extend)]
+        pub fn push_str(&mut self, item: &str) {
+            self.items.push(item.into());
+        }
+
+        #[fluent_impl_opts(extend)]
+        pub fn set_var_ref(&mut self, key: &'_ str, val: &i32) {
+            self.vars.insert(key.into(), *val);
+        }
+
+        pub fn get_items(&self) -> &[String] {
+            &self.items
+        }
+
+        pub fn get_vars(&self) -> &BTreeMap<String, i32> {
+            &self.vars
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Counter {
+        pub count: usize,
+    }
+
+    // Configured out, so the impls don't conflict with the ones below
+    #[fluent_impl(cfg = "any()")]
+    impl Counter {
+        #[fluent_impl_opts(extend, from_iter)]
+        pub fn count(&mut self, _item: u8) {
+            self.count += 1;
+        }
+
+        #[fluent_impl_opts(extend, cfg = "any()")]
+        pub fn count_char(&mut self, _item: char) {
+            self.count += 1;
+        }
+    }
+
+    #[fluent_impl(cfg = "all()")]
+    impl Counter {
+        #[fluent_impl_opts(extend, cfg = "any()")]
+        pub fn count_str(&mut self, _item: &str) {
+            self.count += 1;
+        }
+    }
+
+    impl Extend<u8> for Counter {
+        fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+            self.count += iter.into_iter().count() * 10;
+        }
+    }
+
+    impl<'a> Extend<&'a str> for Counter {
+        fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+            self.count += iter.into_iter().count() * 10;
+        }
+    }
+
+    impl Extend<char> for Counter {
+        fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+            self.count += iter.into_iter().count() * 10;
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Wrapper<'a, T> {
+        pub inner: Vec<&'a T>,
+    }
+
+    // Impl generics and bounds, a non-public method
+    #[fluent_impl(non_public)]
+    impl<'a, T: Debug> Wrapper<'a, T> {
+        #[fluent_impl_opts(extend, from_iter)]
+        fn push_ref(&mut self, item: &'a T) {
+            self.inner.push(item);
+        }
+    }
+}
+
+fn main() {
+    use simple::{Counter, Simple, Wrapper};
+    let mut s: Simple = vec!["a".to_string(), "b".to_string()].into_iter().collect();
+    s.extend(Some("c".to_string()));
+    s.extend(vec![("x", 1), ("y", 2)]);
+    s.extend(vec!["d", "e"]);
+    s.extend(vec![("z", &3)]);
+    assert_eq!(s.get_items(), ["a", "b", "c", "d", "e"]);
+    assert_eq!(s.get_vars().len(), 3);
+
+    let mut c = Counter::default();
+    c.extend(vec![1u8, 2]);
+    c.extend(vec!["a"]);
+    c.extend(vec!['a']);
+    assert_eq!(c.count, 40);
+
+    let nums = [1, 2];
+    let w: Wrapper<i32> = nums.iter().collect();
+    assert_eq!(w.inner, [&1, &2]);
+}
@@ -0,0 +1,44 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+    use std::borrow::Borrow;
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+        text: String,
+    }
+
+    #[fluent_impl(prefix = "and_", deprecated_prefix = "with_")]
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        pub fn add_1(&mut self) {
+            self.num += 1;
+        }
+
+        #[fluent_impl_opts(rename = "appended", deprecated_alias = "with_append, append_with")]
+        pub fn append<S: Borrow<str>>(&mut self, arg: S) {
+            self.text += arg.borrow();
+        }
+
+        // Not affected by deprecated_prefix
+        #[fluent_impl_opts(prefix = "then_")]
+        pub fn sub_1(&mut self) {
+            self.num -= 1;
+        }
+    }
+}
+
+#[allow(deprecated)]
+fn main() {
+    use simple::Simple;
+    let s = Simple::default().and_add_1().and_appended("foo");
+    assert_eq!(s, Simple::default().with_add_1().with_append("foo"));
+    assert_eq!(s, Simple::default().with_add_1().with_appended::<&str>("foo"));
+    assert_eq!(s, Simple::default().and_add_1().append_with("foo"));
+    assert_eq!(Simple::default().then_sub_1().get_num(), -1);
+}
@@ -0,0 +1,75 @@
+#[macro_use]
+extern crate fluent_impl;
+
+pub mod config {
+    use std::collections::HashMap;
+
+    #[derive(Fluent, Default, PartialEq, Debug)]
+    #[fluent(set)]
+    pub struct Config {
+        /// The connection timeout, in seconds.
+        timeout: u32,
+        #[fluent(into)]
+        name: String,
+        #[fluent(strip_option, into)]
+        user: Option<String>,
+        #[fluent(rename = "verbosity", vis = "pub(crate)")]
+        level: u8,
+        #[fluent(each = "with_tag")]
+        tags: Vec<String>,
+        #[fluent(each = "with_env")]
+        envs: HashMap<String, String>,
+        #[fluent(skip)]
+        id: u64,
+        r#type: u8,
+    }
+
+    impl Config {
+        pub fn id(&self) -> u64 {
+            self.id
+        }
+    }
+
+    #[derive(Fluent, Default, PartialEq, Debug)]
+    #[fluent(prefix = "and_")]
+    pub struct Pair<T: Default>(pub T, #[fluent(set)] pub T);
+
+    #[derive(Fluent, Default, PartialEq, Debug)]
+    pub struct Wrapper<T>
+    where
+        T: Clone,
+    {
+        #[fluent(strip_option)]
+        pub inner: Option<T>,
+    }
+}
+
+fn main() {
+    use config::{Config, Pair, Wrapper};
+    let mut c = Config::default()
+        .with_timeout(5)
+        .with_name("config")
+        .with_user("admin")
+        .with_verbosity(2)
+        .with_tag("a".into())
+        .with_tag("b".into())
+        .with_env(("HOME".into(), "/root".into()))
+        .with_type(1);
+    c.set_timeout(10).set_name("other");
+    let expected = Config::default()
+        .with_timeout(10)
+        .with_name("other")
+        .with_user("admin")
+        .with_verbosity(2)
+        .with_tags(vec!["a".into(), "b".into()])
+        .with_envs(vec![("HOME".to_string(), "/root".to_string())].into_iter().collect())
+        .with_type(1);
+    assert_eq!(c, expected);
+    assert_eq!(c.id(), 0);
+
+    let mut p = Pair::default().and_0(1).and_1(2);
+    p.set_1(3);
+    assert_eq!(p, Pair(1, 3));
+
+    assert_eq!(Wrapper::default().with_inner(1).inner, Some(1));
+}
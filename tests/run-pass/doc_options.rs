@@ -0,0 +1,45 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    #[fluent_impl(inherit_doc, doc_alias)]
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        /// Add `step` to `num`.
+        ///
+        /// Placeholders like %f% in the original doc are left alone.
+        #[fluent_impl_opts(see_also)]
+        pub fn add(&mut self, step: i32) {
+            self.num += step;
+        }
+
+        #[fluent_impl_opts(doc = "`%n%(%a%)` takes `self` instead of `%r%`, then calls [`%f%()`].\n\n%d%")]
+        /// Multiply `num` by the product of the factors.
+        pub fn mul(&mut self, factors: &[i32], (a, b): (i32, i32)) {
+            self.num *= factors.iter().product::<i32>() * a * b;
+        }
+    }
+
+    #[fluent_impl(trait = "SimpleExt", prefix = "and_", see_also)]
+    impl Simple {
+        /// Subtract `step` from `num`.
+        pub fn sub(&mut self, step: i32) {
+            self.num -= step;
+        }
+    }
+}
+
+fn main() {
+    use simple::{Simple, SimpleExt};
+    let s = Simple::default().with_add(3).with_mul(&[2], (1, 1)).and_sub(1);
+    assert_eq!(s.get_num(), 5);
+}
@@ -0,0 +1,71 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+    use std::collections::BTreeMap;
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        headers: Vec<String>,
+        vars: BTreeMap<String, i32>,
+        num: i32,
+    }
+
+    #[fluent_impl(each)]
+    impl Simple {
+        pub fn add_header(&mut self, #[fluent(into)] h: String) {
+            self.headers.push(h);
+        }
+
+        #[fluent_impl_opts(each = "with_vars")]
+        pub fn set_var<K: Into<String>>(&mut self, key: K, val: i32) {
+            self.vars.insert(key.into(), val);
+        }
+
+        // No arguments, no each variant
+        pub fn add_1(&mut self) {
+            self.num += 1;
+        }
+
+        pub fn get_headers(&self) -> &[String] {
+            &self.headers
+        }
+
+        pub fn get_vars(&self) -> &BTreeMap<String, i32> {
+            &self.vars
+        }
+    }
+
+    #[fluent_impl(trait = "SimpleExt", prefix = "and_")]
+    impl Simple {
+        #[fluent_impl_opts(each)]
+        pub fn add(&mut self, (a, b): (i32, i32)) {
+            self.num += a * b;
+        }
+
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+    }
+
+    #[fluent_impl(each = "{}_all")]
+    impl Simple {
+        pub fn sub(&mut self, step: i32) {
+            self.num -= step;
+        }
+    }
+}
+
+fn main() {
+    use simple::{Simple, SimpleExt};
+    let s = Simple::default()
+        .with_add_headers(vec!["a", "b"])
+        .with_add_header("c")
+        .with_vars(vec![("x", 1), ("y", 2)])
+        .with_add_1()
+        .and_adds(vec![(2, 3), (1, 1)])
+        .with_sub_all(1..3);
+    assert_eq!(s.get_headers(), ["a", "b", "c"]);
+    assert_eq!(s.get_vars().get("y"), Some(&2));
+    assert_eq!(s.get_num(), 5);
+}
@@ -0,0 +1,50 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+        host: String,
+        port: u16,
+    }
+
+    #[fluent_impl(groups(Network = "Network configuration of [`%t%`].", Math = "Arithmetic."))]
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        pub fn add_1(&mut self) {
+            self.num += 1;
+        }
+
+        #[fluent_impl_opts(group = "Math")]
+        pub fn add_2(&mut self) {
+            self.num += 2;
+        }
+
+        #[fluent_impl_opts(group = "Network")]
+        pub fn set_host(&mut self, host: &str) {
+            self.host = host.into();
+        }
+
+        #[fluent_impl_opts(group = "Network")]
+        pub fn set_port(&mut self, port: u16) {
+            self.port = port;
+        }
+
+        // Groups don't apply to inblock methods
+        #[fluent_impl_opts(group = "Network", inblock)]
+        pub fn reset_port(&mut self) {
+            self.port = 0;
+        }
+    }
+}
+
+fn main() {
+    use simple::Simple;
+    let s = Simple::default().with_add_1().with_add_2().with_set_host("localhost").with_set_port(80);
+    assert_eq!(s.get_num(), 3);
+}
@@ -0,0 +1,64 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::fluent_impl;
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    #[fluent_impl(include = "set_*, add_?", exclude = "*_raw, add_3", strict)]
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        pub fn set_num(&mut self, num: i32) {
+            self.num = num;
+        }
+
+        pub fn set_num_raw(&mut self, num: i32) {
+            self.num = num;
+        }
+
+        pub fn add_1(&mut self) {
+            self.num += 1;
+        }
+
+        pub fn add_3(&mut self) {
+            self.num += 3;
+        }
+
+        pub fn add_10(&mut self) {
+            self.num += 10;
+        }
+    }
+
+    pub trait Fallback {
+        fn with_set_num_raw(self, num: i32) -> Self;
+        fn with_add_3(self) -> Self;
+        fn with_add_10(self) -> Self;
+    }
+
+    // Used where no chaining method is generated
+    impl Fallback for Simple {
+        fn with_set_num_raw(self, _num: i32) -> Self {
+            self
+        }
+
+        fn with_add_3(self) -> Self {
+            self
+        }
+
+        fn with_add_10(self) -> Self {
+            self
+        }
+    }
+}
+
+fn main() {
+    use simple::{Fallback, Simple};
+    let s = Simple::default().with_set_num(5).with_set_num_raw(100).with_add_1().with_add_3().with_add_10();
+    assert_eq!(s.get_num(), 6);
+}
@@ -0,0 +1,80 @@
+// rustc-env:CARGO_MANIFEST_DIR=tests/manifest/valid
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    // non_public and prefix = "and_" from the manifest
+    #[fluent_impl]
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        pub(crate) fn add_1(&mut self) {
+            self.num += 1;
+        }
+    }
+
+    // prefix = "with_" from the builder preset, and non_public turned off
+    #[fluent_impl(preset = "builder")]
+    impl Simple {
+        pub fn add_2(&mut self) {
+            self.num += 2;
+        }
+
+        // Skipped, with_add_5() would conflict otherwise
+        pub(crate) fn add_5(&mut self) {
+            self.num += 5;
+        }
+
+        pub fn with_add_5(mut self) -> Self {
+            self.add_5();
+            self
+        }
+    }
+
+    // Attribute options override the manifest, and can turn its words off
+    #[fluent_impl(non_public = false)]
+    impl Simple {
+        pub fn add_6(&mut self) {
+            self.num += 6;
+        }
+
+        // Skipped, and_add_7() would conflict otherwise
+        pub(crate) fn add_7(&mut self) {
+            self.num += 7;
+        }
+
+        pub fn and_add_7(mut self) -> Self {
+            self.add_7();
+            self
+        }
+    }
+
+    // Attribute options override presets
+    #[fluent_impl(preset = "chain", prefix = "ch_")]
+    impl Simple {
+        pub(crate) fn add_3(&mut self) {
+            self.num += 3;
+        }
+
+        // Method options override everything, the group is from the chain preset
+        #[fluent_impl_opts(prefix = "plus_", group = "Math")]
+        pub(crate) fn add_4(&mut self) {
+            self.num += 4;
+        }
+    }
+}
+
+fn main() {
+    use simple::Simple;
+    let s = Simple::default().and_add_1().with_add_2().ch_add_3().plus_add_4();
+    assert_eq!(s.get_num(), 10);
+    assert_eq!(s.and_add_6().and_add_7().get_num(), 23);
+}
@@ -0,0 +1,79 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+        color: String,
+    }
+
+    #[fluent_impl(strip_prefix = "set_", prefix = "")]
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        pub fn set_color(&mut self, color: &str) {
+            self.color = color.into();
+        }
+
+        #[fluent_impl_opts(prefix = "with_", suffix = "_set")]
+        pub fn set_num(&mut self, num: i32) {
+            self.num = num;
+        }
+
+        #[fluent_impl_opts(name_template = "with_{}_applied")]
+        pub fn add_1(&mut self) {
+            self.num += 1;
+        }
+
+        #[fluent_impl_opts(prefix = "with_", case = "snake")]
+        #[allow(non_snake_case)]
+        pub fn addTwo(&mut self) {
+            self.num += 2;
+        }
+
+        #[fluent_impl_opts(prefix = "with_")]
+        pub fn r#type(&mut self) {
+            self.color = "type".into();
+        }
+
+        #[fluent_impl_opts(name = "match")]
+        pub fn set_match(&mut self) {
+            self.color = "match".into();
+        }
+    }
+
+    #[fluent_impl(name_template = "and_{}_too", strip_prefix = "set_")]
+    impl Simple {
+        pub fn set_double(&mut self) {
+            self.num *= 2;
+        }
+
+        #[fluent_impl_opts(prefix = "then_")]
+        pub fn set_triple(&mut self) {
+            self.num *= 3;
+        }
+    }
+}
+
+fn main() {
+    use simple::Simple;
+    let s = Simple::default()
+        .with_num_set(1)
+        .color("red")
+        .with_add_1_applied()
+        .with_add_two()
+        .and_double_too()
+        .then_triple();
+    assert_eq!(s.get_num(), 24);
+    assert_eq!(s, Simple::default().with_num_set(24).color("red"));
+    let mut s = Simple::default();
+    s.r#type();
+    assert_eq!(Simple::default().with_type(), s);
+    let mut s = Simple::default();
+    s.set_match();
+    assert_eq!(Simple::default().r#match(), s);
+}
@@ -0,0 +1,76 @@
+extern crate fluent_impl;
+
+pub mod config {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct TlsConfig {
+        pub verify: bool,
+        pub cert: Option<String>,
+    }
+
+    #[fluent_impl]
+    impl TlsConfig {
+        pub fn set_verify(&mut self, verify: bool) {
+            self.verify = verify;
+        }
+
+        pub fn set_cert(&mut self, cert: &str) {
+            self.cert = Some(cert.into());
+        }
+    }
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Config {
+        tls: TlsConfig,
+        retries: u32,
+        names: Vec<String>,
+    }
+
+    #[fluent_impl]
+    impl Config {
+        #[fluent_impl_opts(nested_chain)]
+        pub fn tls_mut(&mut self) -> &mut TlsConfig {
+            &mut self.tls
+        }
+
+        #[fluent_impl_opts(nested, rename = "name_list")]
+        pub fn names_mut(&mut self) -> &mut Vec<String> {
+            &mut self.names
+        }
+
+        pub fn set_retries(&mut self, retries: u32) {
+            self.retries = retries;
+        }
+
+        pub fn get_tls(&self) -> &TlsConfig {
+            &self.tls
+        }
+
+        pub fn get_names(&self) -> &[String] {
+            &self.names
+        }
+    }
+
+    #[fluent_impl(trait = "ConfigExt", prefix = "and_")]
+    impl Config {
+        #[fluent_impl_opts(nested)]
+        pub fn retries_mut(&mut self) -> &mut u32 {
+            &mut self.retries
+        }
+    }
+}
+
+fn main() {
+    use config::{Config, ConfigExt, TlsConfig};
+    let c = Config::default()
+        .with_tls(|tls| tls.set_verify(true))
+        .with_name_list(|names| names.push("a".into()))
+        .and_retries(|r| *r += 2)
+        .with_set_retries(1);
+    assert!(c.get_tls().verify);
+    assert_eq!(c.get_names(), ["a"]);
+
+    let c = c.with_tls_chain(|tls| tls.with_set_cert("cert.pem").with_set_verify(false));
+    assert_eq!(c.get_tls(), &TlsConfig { verify: false, cert: Some("cert.pem".into()) });
+}
@@ -0,0 +1,132 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+    use std::ops::{Div, DivAssign, Sub, SubAssign};
+    use std::path::{Path, PathBuf};
+
+    #[derive(Clone, Copy, Default, PartialEq, Debug)]
+    pub struct Vec3 {
+        pub x: i32,
+        pub y: i32,
+        pub z: i32,
+    }
+
+    #[fluent_impl]
+    impl Vec3 {
+        #[fluent_impl_opts(op = "AddAssign")]
+        pub fn add_assign_vec(&mut self, v: Vec3) {
+            self.x += v.x;
+            self.y += v.y;
+            self.z += v.z;
+        }
+
+        #[fluent_impl_opts(op = "MulAssign", skip)]
+        pub fn scale(&mut self, factor: i32) {
+            self.x *= factor;
+            self.y *= factor;
+            self.z *= factor;
+        }
+
+        #[fluent_impl_opts(op = "ShlAssign")]
+        pub fn shift<N: Into<u32>>(&mut self, n: N) {
+            let n = n.into();
+            self.x <<= n;
+            self.y <<= n;
+            self.z <<= n;
+        }
+    }
+
+    // Configured out, so the impls don't conflict with the ones below
+    #[fluent_impl(cfg = "any()")]
+    impl Vec3 {
+        #[fluent_impl_opts(op = "SubAssign")]
+        pub fn sub_assign_vec(&mut self, v: Vec3) {
+            self.x -= v.x;
+            self.y -= v.y;
+            self.z -= v.z;
+        }
+    }
+
+    #[fluent_impl(cfg = "all()")]
+    impl Vec3 {
+        #[fluent_impl_opts(op = "DivAssign", cfg = "any()")]
+        pub fn div_assign_vec(&mut self, d: i32) {
+            self.x /= d;
+            self.y /= d;
+            self.z /= d;
+        }
+    }
+
+    impl SubAssign<Vec3> for Vec3 {
+        fn sub_assign(&mut self, _v: Vec3) {}
+    }
+
+    impl Sub<Vec3> for Vec3 {
+        type Output = Vec3;
+        fn sub(self, _v: Vec3) -> Vec3 {
+            self
+        }
+    }
+
+    impl DivAssign<i32> for Vec3 {
+        fn div_assign(&mut self, _d: i32) {}
+    }
+
+    impl Div<i32> for Vec3 {
+        type Output = Vec3;
+        fn div(self, _d: i32) -> Vec3 {
+            self
+        }
+    }
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Route {
+        pub path: PathBuf,
+    }
+
+    // Elided lifetime in the argument type
+    #[fluent_impl]
+    impl Route {
+        #[fluent_impl_opts(op = "AddAssign")]
+        pub fn add_path(&mut self, p: &Path) {
+            self.path.push(p);
+        }
+    }
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Flags<T> {
+        pub set: Vec<T>,
+    }
+
+    #[fluent_impl]
+    impl<T: PartialEq> Flags<T> {
+        #[fluent_impl_opts(op = "BitOrAssign")]
+        pub fn insert(&mut self, flag: T) {
+            if !self.set.contains(&flag) {
+                self.set.push(flag);
+            }
+        }
+    }
+}
+
+fn main() {
+    use simple::{Flags, Route, Vec3};
+    use std::path::Path;
+    let one = Vec3 { x: 1, y: 1, z: 1 };
+    let mut v = Vec3::default() + one;
+    v += one;
+    assert_eq!(v, Vec3 { x: 2, y: 2, z: 2 });
+    v *= 3;
+    assert_eq!(v * 2, Vec3 { x: 12, y: 12, z: 12 });
+    assert_eq!(v.with_add_assign_vec(one) << 1u8, Vec3 { x: 14, y: 14, z: 14 });
+    assert_eq!((v - one) / 2, v);
+
+    let mut route = Route::default() + Path::new("a");
+    route += Path::new("b");
+    assert_eq!(route.path, Path::new("a/b"));
+
+    let mut flags = Flags::default() | "a" | "b";
+    flags |= "a";
+    assert_eq!(flags.set, ["a", "b"]);
+}
@@ -0,0 +1,86 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+    use std::collections::HashMap;
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        name: String,
+        num: Option<i32>,
+        tags: HashMap<String, u8>,
+        scale: i32,
+    }
+
+    #[fluent_impl(opt_variant)]
+    impl Simple {
+        pub fn set_name(&mut self, #[fluent(into)] name: String) {
+            self.name = name;
+        }
+
+        pub fn set_num(&mut self, #[fluent(strip_option)] num: Option<i32>) {
+            self.num = num;
+        }
+
+        #[fluent_impl_opts(name = "with_tag")]
+        pub fn add_tag(&mut self, #[fluent(into)] tag: String, #[fluent(default = "1 + 1")] weight: u8) {
+            self.tags.insert(tag, weight);
+        }
+
+        pub fn set_tags(&mut self, tags: HashMap<String, u8>, #[fluent(strip_option, into)] scale: Option<i32>) {
+            self.tags = tags;
+            self.scale = scale.unwrap_or(1);
+        }
+
+        pub fn get_name(&self) -> &str {
+            &self.name
+        }
+
+        pub fn get_num(&self) -> Option<i32> {
+            self.num
+        }
+
+        pub fn get_tags(&self) -> &HashMap<String, u8> {
+            &self.tags
+        }
+
+        pub fn get_scale(&self) -> i32 {
+            self.scale
+        }
+    }
+
+    #[fluent_impl(trait = "SimpleExt")]
+    impl Simple {
+        pub fn scale_by(&mut self, #[fluent(into)] factor: i32) {
+            self.scale *= factor;
+        }
+    }
+
+    pub struct Other(pub Vec<String>);
+
+    impl Other {
+        #[fluent_impl]
+        pub fn push(&mut self, #[fluent(into)] s: String) {
+            self.0.push(s);
+        }
+    }
+}
+
+fn main() {
+    use simple::{Other, Simple, SimpleExt};
+    let s = Simple::default()
+        .with_set_name("simple")
+        .with_set_num(5)
+        .with_tag("a")
+        .with_set_num_opt(None);
+    assert_eq!(s.get_name(), "simple");
+    assert_eq!(s.get_num(), Some(5));
+    assert_eq!(s.get_tags().get("a"), Some(&2));
+
+    let s = s.with_set_tags(Default::default(), 3i8).with_scale_by(2u8);
+    assert!(s.get_tags().is_empty());
+    assert_eq!(s.get_scale(), 6);
+
+    let o = Other(Vec::new()).with_push("a").with_push(String::from("b"));
+    assert_eq!(o.0, ["a", "b"]);
+}
@@ -0,0 +1,93 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub enum Color {
+        Red,
+        Blue,
+        Rgb(u8, u8, u8),
+    }
+
+    #[derive(PartialEq, Debug)]
+    pub struct Simple {
+        color: Color,
+        enabled: bool,
+        verbose: bool,
+        num: i32,
+    }
+
+    impl Default for Simple {
+        fn default() -> Self {
+            Self { color: Color::Blue, enabled: false, verbose: false, num: 0 }
+        }
+    }
+
+    #[fluent_impl(strip_prefix = "set_")]
+    impl Simple {
+        #[fluent_impl_opts(preset(name = "with_red", args = "Color::Red"), preset(name = "with_gray", args = "Color::Rgb(128, 128, 128)"))]
+        pub fn set_color(&mut self, color: Color) {
+            self.color = color;
+        }
+
+        #[fluent_impl_opts(flag)]
+        pub fn set_enabled(&mut self, on: bool) {
+            self.enabled = on;
+        }
+
+        #[fluent_impl_opts(flag(on = "verbose", off = "quiet"))]
+        pub fn set_verbose(&mut self, verbose: bool) {
+            self.verbose = verbose;
+        }
+
+        // Presets of a chaining method with parameter options
+        #[fluent_impl_opts(preset(name = "with_one", args = "1"))]
+        pub fn set_num(&mut self, #[fluent(strip_option)] num: Option<i32>) {
+            self.num = num.unwrap_or_default();
+        }
+
+        pub fn get_color(&self) -> Color {
+            self.color
+        }
+
+        pub fn is_enabled(&self) -> bool {
+            self.enabled
+        }
+
+        pub fn is_verbose(&self) -> bool {
+            self.verbose
+        }
+
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+    }
+
+    #[fluent_impl(trait = "SimpleExt", prefix = "and_")]
+    impl Simple {
+        #[fluent_impl_opts(flag(off = "and_not_enabled"))]
+        pub fn toggle_enabled(&mut self, on: bool) {
+            self.enabled = on;
+            if !on {
+                self.color = Color::Blue;
+            }
+        }
+    }
+}
+
+fn main() {
+    use simple::{Color, Simple, SimpleExt};
+    let s = Simple::default().with_red().with_enabled().verbose().with_one();
+    assert_eq!(s.get_color(), Color::Red);
+    assert!(s.is_enabled() && s.is_verbose());
+    assert_eq!(s.get_num(), 1);
+
+    let s = s.with_gray().without_enabled().quiet();
+    assert_eq!(s.get_color(), Color::Rgb(128, 128, 128));
+    assert!(!s.is_enabled() && !s.is_verbose());
+
+    let s = s.with_color(Color::Red).and_toggle_enabled().and_not_enabled();
+    assert!(!s.is_enabled());
+    assert_eq!(s.get_color(), Color::Blue);
+}
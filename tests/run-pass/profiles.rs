@@ -0,0 +1,54 @@
+extern crate fluent_impl;
+
+pub mod m {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+    use std::ops::AddAssign;
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct St<A: AddAssign> {
+        value: A,
+        text: String,
+    }
+
+    #[fluent_impl(profile = "owned")]
+    #[fluent_impl(profile = "ext", prefix = "and_", trait = "StExt")]
+    #[fluent_impl(profile = "inline", prefix = "also_", inblock)]
+    impl<A: AddAssign> St<A> {
+        pub fn get_value(&self) -> &A {
+            &self.value
+        }
+
+        pub fn get_text(&self) -> &str {
+            &self.text
+        }
+
+        #[fluent_impl_opts(profile = "owned", rename = "added_value")]
+        pub fn add_value(&mut self, to_be_added: A) {
+            self.value += to_be_added;
+        }
+
+        #[fluent_impl_opts(profile = "ext", skip)]
+        #[fluent_impl_opts(profile = "inline", skip)]
+        pub fn append_text(&mut self, mut arg: String) {
+            arg += "!";
+            self.text += &arg;
+        }
+    }
+}
+
+pub mod other {
+    // Only the extension trait is needed to use `and_` methods
+    use m::{St, StExt};
+
+    pub fn make() -> St<u32> {
+        St::default().and_add_value(3)
+    }
+}
+
+fn main() {
+    use m::St;
+    let s = St::default().with_added_value(3u32).with_append_text("a".into());
+    assert_eq!(s.get_value(), &3);
+    assert_eq!(s.get_text(), "a!");
+    assert_eq!(other::make().also_add_value(1), St::default().with_added_value(4));
+}
@@ -0,0 +1,76 @@
+extern crate fluent_impl;
+
+pub mod config {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+    use std::time::Duration;
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Config {
+        timeout: Duration,
+        name: String,
+        retries: u32,
+        tags: Vec<String>,
+    }
+
+    #[fluent_impl]
+    impl Config {
+        #[fluent_impl_opts(setter)]
+        pub fn timeout_mut(&mut self) -> &mut Duration {
+            &mut self.timeout
+        }
+
+        #[fluent_impl_opts(setter_into, opt_variant)]
+        pub fn name_mut(&mut self) -> &mut String {
+            &mut self.name
+        }
+
+        pub fn get_timeout(&self) -> Duration {
+            self.timeout
+        }
+
+        pub fn get_name(&self) -> &str {
+            &self.name
+        }
+    }
+
+    // All `*_mut` accessors
+    #[fluent_impl(setters, prefix = "set_")]
+    impl Config {
+        pub fn retries_mut(&mut self) -> &mut u32 {
+            &mut self.retries
+        }
+
+        #[fluent_impl_opts(nested)]
+        pub fn tags_mut(&mut self) -> &mut Vec<String> {
+            &mut self.tags
+        }
+
+        // Not named like an accessor
+        pub fn first_tag(&mut self) -> &mut String {
+            &mut self.tags[0]
+        }
+
+        pub fn get_retries(&self) -> u32 {
+            self.retries
+        }
+
+        pub fn get_tags(&self) -> &[String] {
+            &self.tags
+        }
+    }
+}
+
+fn main() {
+    use config::Config;
+    use std::time::Duration;
+    let c = Config::default()
+        .with_timeout(Duration::from_secs(5))
+        .with_name("config")
+        .with_name_opt(None::<String>)
+        .set_retries(3)
+        .set_tags(|tags| tags.push("a".into()));
+    assert_eq!(c.get_timeout(), Duration::from_secs(5));
+    assert_eq!(c.get_name(), "config");
+    assert_eq!(c.get_retries(), 3);
+    assert_eq!(c.get_tags(), ["a"]);
+}
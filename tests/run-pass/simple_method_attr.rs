@@ -0,0 +1,47 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    impl Simple {
+        pub fn new(n: i32) -> Self {
+            Self { num: n }
+        }
+
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        #[fluent_impl]
+        pub fn add_1(&mut self) {
+            self.num += 1;
+        }
+
+        #[fluent_impl(non_public, prefix = "w_")]
+        #[fluent_impl_opts(rename = "added")]
+        pub(crate) fn add(&mut self, n: i32) {
+            self.num += n;
+        }
+
+        // Not chain-able, untouched
+        pub fn sub_1(&mut self) -> i32 {
+            self.num -= 1;
+            self.num
+        }
+    }
+}
+
+fn main() {
+    use simple::Simple;
+    let mut s1 = Simple::default();
+    s1.add_1();
+    assert_eq!(s1.get_num(), 1);
+    assert_eq!(s1, Simple::default().with_add_1());
+    assert_eq!(Simple::new(3), Simple::default().w_added(2).with_add_1());
+    assert_eq!(Simple::new(4).sub_1(), 3);
+}
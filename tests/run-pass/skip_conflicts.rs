@@ -0,0 +1,55 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        v: Vec<u32>,
+    }
+
+    #[fluent_impl(skip_conflicts)]
+    impl Simple {
+        pub fn with_capacity(n: usize) -> Self {
+            Self { v: Vec::with_capacity(n) }
+        }
+
+        pub fn get_v(&self) -> &[u32] {
+            &self.v
+        }
+
+        // with_capacity exists, skipped
+        pub fn capacity(&mut self) {
+            self.v.shrink_to_fit();
+        }
+
+        pub fn push_1(&mut self) {
+            self.v.push(1);
+        }
+    }
+
+    #[fluent_impl(profile = "with")]
+    #[fluent_impl(profile = "and", prefix = "and_", trait = "SimpleExt")]
+    impl Simple {
+        pub fn push_2(&mut self) {
+            self.v.push(2);
+        }
+
+        // Generated first, wins
+        #[fluent_impl_opts(rename = "push_3")]
+        pub fn push_3_first(&mut self) {
+            self.v.push(3);
+        }
+
+        #[fluent_impl_opts(rename = "push_3", skip_conflicts)]
+        pub fn push_3_second(&mut self) {
+            self.v.push(33);
+        }
+    }
+}
+
+fn main() {
+    use simple::{Simple, SimpleExt};
+    let s = Simple::with_capacity(4).with_push_1().with_push_2().with_push_3().and_push_3();
+    assert_eq!(s.get_v(), &[1, 2, 3, 3]);
+}
@@ -0,0 +1,70 @@
+extern crate fluent_impl;
+
+pub mod request {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Request {
+        url: String,
+        method: String,
+        headers: Vec<(String, String)>,
+    }
+
+    #[fluent_impl(builder = "RequestBuilder", builder_default, if_variant)]
+    impl Request {
+        #[fluent_impl_opts(required, deprecated_alias = "with_uri")]
+        pub fn set_url(&mut self, #[fluent(into)] url: String) {
+            self.url = url;
+        }
+
+        #[fluent_impl_opts(required, preset(name = "with_get", args = "\"GET\""))]
+        pub fn set_method(&mut self, method: &str) {
+            self.method = method.into();
+        }
+
+        pub fn add_header(&mut self, name: &str, value: &str) {
+            self.headers.push((name.into(), value.into()));
+        }
+
+        pub fn url(&self) -> &str {
+            &self.url
+        }
+    }
+
+    pub struct Pair<'a, T>(pub &'a str, pub T);
+
+    #[fluent_impl(builder = "PairBuilder", prefix = "")]
+    impl<'a, T: Clone> Pair<'a, T> {
+        #[fluent_impl_opts(required)]
+        pub fn set_value(&mut self, value: T) {
+            self.1 = value;
+        }
+
+        // `Self` is the built type, not the builder
+        pub fn set_name_from(&mut self, other: &Self) {
+            self.0 = other.0;
+        }
+    }
+}
+
+fn main() {
+    use request::{Pair, PairBuilder, RequestBuilder};
+    #[allow(deprecated)]
+    let request = RequestBuilder::default()
+        .with_add_header("a", "b")
+        .with_add_header_if(false, "c", "d")
+        .with_uri("http://localhost")
+        .with_add_header("e", "f")
+        .with_get()
+        .with_set_url("http://example.com")
+        .build();
+    assert_eq!(request.url(), "http://example.com");
+
+    let request = RequestBuilder::from(request).with_set_method("POST").with_set_url("x").build();
+    assert_eq!(request.url(), "x");
+
+    let pair = PairBuilder::from(Pair("a", 1)).set_value(2).build();
+    assert_eq!(pair.1, 2);
+    let pair = PairBuilder::from(pair).set_name_from(&Pair("b", 0)).set_value(3).build();
+    assert_eq!((pair.0, pair.1), ("b", 3));
+}
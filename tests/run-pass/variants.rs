@@ -0,0 +1,63 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+        flags: Vec<String>,
+    }
+
+    #[fluent_impl(if_variant)]
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        pub fn add_1(&mut self) {
+            self.num += 1;
+        }
+
+        #[fluent_impl_opts(opt_variant)]
+        pub fn add(&mut self, cond: i32) {
+            self.num += cond;
+        }
+
+        #[fluent_impl_opts(opt_variant)]
+        pub fn add_flag<S: Into<String>>(&mut self, flag: S, times: usize) {
+            let flag = flag.into();
+            for _ in 0..times {
+                self.flags.push(flag.clone());
+            }
+        }
+
+        pub fn get_flags(&self) -> &[String] {
+            &self.flags
+        }
+    }
+
+    #[fluent_impl(trait = "SimpleExt", prefix = "and_", opt_variant, if_variant)]
+    impl Simple {
+        pub fn sub(&mut self, (a, b): (i32, i32)) {
+            self.num -= a + b;
+        }
+    }
+}
+
+fn main() {
+    use simple::{Simple, SimpleExt};
+    let s = Simple::default()
+        .with_add_1_if(true)
+        .with_add_1_if(false)
+        .with_add_if(true, 10)
+        .with_add_opt(Some(5))
+        .with_add_opt(None)
+        .with_add_flag_opt(Some(("verbose", 1)))
+        .with_add_flag_opt(None::<(&str, usize)>)
+        .with_add_flag_if(false, "quiet", 1)
+        .and_sub_opt(Some((1, 1)))
+        .and_sub_if(false, (5, 5));
+    assert_eq!(s.get_num(), 14);
+    assert_eq!(s.get_flags(), &["verbose".to_string()]);
+}
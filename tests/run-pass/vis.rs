@@ -0,0 +1,55 @@
+extern crate fluent_impl;
+
+pub mod simple {
+    use fluent_impl::{fluent_impl, fluent_impl_opts};
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Simple {
+        num: i32,
+    }
+
+    #[fluent_impl(vis = "pub(crate)", hidden)]
+    impl Simple {
+        pub fn get_num(&self) -> i32 {
+            self.num
+        }
+
+        // Public chaining method for a private mutator
+        #[fluent_impl_opts(vis = "pub")]
+        fn add_1(&mut self) {
+            self.num += 1;
+        }
+
+        // Crate-private chaining method for a public mutator
+        pub fn add_2(&mut self) {
+            self.num += 2;
+        }
+    }
+
+    #[fluent_impl(trait = "SimpleExt", vis = "pub(crate)", prefix = "and_")]
+    impl Simple {
+        pub fn add_3(&mut self) {
+            self.num += 3;
+        }
+    }
+
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Wrapper<T> {
+        pub(crate) inner: T,
+    }
+
+    #[fluent_impl(vis = "pub", non_public)]
+    impl<T: Clone> Wrapper<T> {
+        // Generic and `Self` argument types
+        pub(crate) fn set(&mut self, inner: &T, _other: &Self) {
+            self.inner = inner.clone();
+        }
+    }
+}
+
+fn main() {
+    use simple::{Simple, SimpleExt, Wrapper};
+    assert_eq!(Simple::default().with_add_1().with_add_2().and_add_3().get_num(), 6);
+    let other = Wrapper::default();
+    assert_eq!(Wrapper::default().with_set(&3, &other).inner, 3);
+}